@@ -1,4 +1,4 @@
-use std::alloc::{Global, Alloc, Layout, System};
+use std::alloc::{Global, Alloc, Excess, Layout, System};
 
 /// Issue #45955.
 #[test]
@@ -30,3 +30,193 @@ fn check_overalign_requests<T: Alloc>(mut allocator: T) {
         }
     }
 }
+
+#[test]
+fn alloc_system_zeroed_request() {
+    check_zeroed_requests(System)
+}
+
+#[test]
+fn std_heap_zeroed_request() {
+    check_zeroed_requests(Global)
+}
+
+/// Checks that `alloc_zeroed` hands out zeroed memory on both the
+/// `calloc`/`HEAP_ZERO_MEMORY` fast path (small alignment) and the
+/// `alloc` + `write_bytes` fallback (alignment above `MIN_ALIGN` or above
+/// the size). Each block is dirtied before being freed so that a reused
+/// block which skipped zeroing would be caught on the next round.
+fn check_zeroed_requests<T: Alloc>(mut allocator: T) {
+    let sizes = [1, 7, 64, 4096];
+    let aligns = [1, 8, 64, 4096];
+    let rounds = 4;
+    unsafe {
+        for _ in 0..rounds {
+            for &size in &sizes {
+                for &align in &aligns {
+                    let layout = Layout::from_size_align(size, align).unwrap();
+                    let ptr = allocator.alloc_zeroed(layout.clone()).unwrap();
+                    assert_eq!((ptr.as_ptr() as usize) % align, 0,
+                               "Got a pointer less aligned than requested");
+
+                    let bytes = std::slice::from_raw_parts_mut(ptr.as_ptr(), size);
+                    assert!(bytes.iter().all(|&b| b == 0),
+                            "Got non-zeroed memory for size {} align {}", size, align);
+                    for b in bytes.iter_mut() {
+                        *b = 0xa5;
+                    }
+
+                    allocator.dealloc(ptr, layout);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn alloc_system_struct_array() {
+    unsafe {
+        let (ptr, layout) = System.alloc_struct_array::<u32>(10, 7).unwrap();
+        assert_eq!(layout.size(), 10 * 4 + 7);
+        assert_eq!(layout.align(), std::mem::align_of::<u32>());
+        assert_eq!((ptr.as_ptr() as usize) % layout.align(), 0);
+
+        for i in 0..10 {
+            *ptr.as_ptr().add(i) = i as u32;
+        }
+        let tail = ptr.as_ptr().add(10) as *mut u8;
+        for i in 0..7 {
+            *tail.add(i) = 0xa0 + i as u8;
+        }
+        for i in 0..10 {
+            assert_eq!(*ptr.as_ptr().add(i), i as u32);
+        }
+        for i in 0..7 {
+            assert_eq!(*tail.add(i), 0xa0 + i as u8);
+        }
+
+        System.dealloc(ptr.cast(), layout);
+    }
+}
+
+#[test]
+fn alloc_system_struct_array_overflow() {
+    assert!(System.alloc_struct_array::<u64>(usize::max_value() / 4, 0).is_err());
+    assert!(System.alloc_struct_array::<u32>(1, usize::max_value() - 2).is_err());
+}
+
+#[test]
+fn alloc_system_array_zeroed() {
+    let n = 1000;
+    unsafe {
+        let ptr = System.alloc_array_zeroed::<u64>(n).unwrap();
+        assert_eq!((ptr.as_ptr() as usize) % std::mem::align_of::<u64>(), 0);
+        let values = std::slice::from_raw_parts(ptr.as_ptr(), n);
+        assert!(values.iter().all(|&v| v == 0));
+
+        System.dealloc(ptr.cast(), Layout::array::<u64>(n).unwrap());
+    }
+}
+
+#[test]
+fn alloc_system_array_zeroed_overflow() {
+    assert!(System.alloc_array_zeroed::<u64>(usize::max_value() / 4).is_err());
+    assert!(System.alloc_array_zeroed::<u8>(0).is_err());
+}
+
+#[cfg(all(any(unix, windows), target_thread_local))]
+#[test]
+fn alloc_system_last_error() {
+    #[cfg(unix)]
+    use std::os::unix::alloc::SystemExt;
+    #[cfg(windows)]
+    use std::os::windows::alloc::SystemExt;
+    use std::thread;
+
+    // Each thread has its own record, and a new thread starts out clean.
+    thread::spawn(|| {
+        assert_eq!(System.last_error(), None);
+
+        let layout = Layout::from_size_align(isize::max_value() as usize, 1).unwrap();
+        assert!(unsafe { System.alloc(layout) }.is_err());
+        let error = System.last_error();
+        assert!(error.is_some());
+
+        // Successful allocations do not clear it.
+        let layout = Layout::from_size_align(8, 8).unwrap();
+        unsafe {
+            let ptr = System.alloc(layout.clone()).unwrap();
+            System.dealloc(ptr, layout);
+        }
+        assert_eq!(System.last_error(), error);
+    }).join().unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+#[test]
+fn alloc_system_realloc_aligned_in_place() {
+    // The allocator rounds the block up, so growing it by a byte fits.
+    let layout = Layout::from_size_align(100, 64).unwrap();
+    unsafe {
+        let ptr = System.alloc(layout.clone()).unwrap();
+        for i in 0..100 {
+            *ptr.as_ptr().add(i) = i as u8;
+        }
+
+        let grown = System.realloc(ptr, layout, 101).unwrap();
+        assert_eq!(grown, ptr);
+        for i in 0..100 {
+            assert_eq!(*grown.as_ptr().add(i), i as u8);
+        }
+
+        System.dealloc(grown, Layout::from_size_align(101, 64).unwrap());
+    }
+}
+
+#[test]
+fn alloc_system_excess() {
+    unsafe {
+        for &(size, align) in &[(1, 1), (100, 8), (100, 64), (4000, 16)] {
+            let layout = Layout::from_size_align(size, align).unwrap();
+            let Excess(ptr, usable) = System.alloc_excess(layout.clone()).unwrap();
+            assert!(usable >= size);
+            assert_eq!(ptr.as_ptr() as usize % align, 0);
+
+            // The whole usable size can be written, and freed with.
+            for i in 0..usable {
+                *ptr.as_ptr().add(i) = i as u8;
+            }
+            let grown = Layout::from_size_align(usable, align).unwrap();
+            let Excess(ptr, usable) = System.realloc_excess(ptr, grown, size * 2).unwrap();
+            assert!(usable >= size * 2);
+            System.dealloc(ptr, Layout::from_size_align(usable, align).unwrap());
+        }
+    }
+}
+
+#[cfg(windows)]
+#[test]
+fn alloc_system_many_blocks() {
+    use std::thread;
+
+    // The first allocations of several threads race to look up the process
+    // heap; every block must still come from, and go back to, the same heap.
+    let threads: Vec<_> = (0..4).map(|t| thread::spawn(move || unsafe {
+        let mut blocks = Vec::new();
+        for i in 0..1000 {
+            let align = if i % 2 == 0 { 8 } else { 64 };
+            let layout = Layout::from_size_align(16 + i % 100, align).unwrap();
+            let ptr = System.alloc(layout.clone()).unwrap();
+            assert_eq!(ptr.as_ptr() as usize % align, 0);
+            *ptr.as_ptr() = (t + i) as u8;
+            blocks.push((ptr, layout));
+        }
+        for (i, (ptr, layout)) in blocks.into_iter().enumerate() {
+            assert_eq!(*ptr.as_ptr(), (t + i) as u8);
+            System.dealloc(ptr, layout);
+        }
+    })).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
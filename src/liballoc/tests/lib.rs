@@ -1,5 +1,8 @@
 #![feature(allocator_api)]
+#![feature(alloc_last_error)]
+#![feature(alloc_system_ext)]
 #![feature(box_syntax)]
+#![feature(cfg_target_thread_local)]
 #![feature(drain_filter)]
 #![feature(exact_size_is_empty)]
 #![feature(pattern)]
@@ -1308,6 +1308,8 @@ where
 
             time(sess, "layout testing", || layout_test::test_layout(tcx));
 
+            time(sess, "purity testing", || mir::transform::purity::test_purity(tcx));
+
             // Avoid overwhelming user with errors if borrow checking failed.
             // I'm not sure how helpful this is, to be honest, but it avoids
             // a
@@ -0,0 +1,115 @@
+//! A pass that turns `clone` calls on `Copy` types into copies.
+//!
+//! `x.clone()` on a `u32`, a shared reference or a tuple of those is a call
+//! that only copies `*self`. The call is replaced by an assignment of a copy
+//! of the value behind the receiver.
+//!
+//! A `Copy` type may still have a hand-written `Clone` impl that does more
+//! than copying, so only the impls that are known to copy are replaced: the
+//! core library's impls for primitive types and shared references, and the
+//! compiler's own clone shims for tuples and arrays of `Copy` types.
+
+use rustc::mir::*;
+use rustc::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc::ty::subst::Substs;
+use rustc::hir::def_id::DefId;
+use crate::transform::{MirPass, MirSource};
+
+pub struct CloneToCopy;
+
+impl MirPass for CloneToCopy {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when optimizing MIR (at any level).
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return
+        }
+        let clone_trait = match tcx.lang_items().clone_trait() {
+            Some(clone_trait) => clone_trait,
+            None => return,
+        };
+
+        let param_env = tcx.param_env(source.def_id());
+        let mut copies = vec![];
+        for (bb, data) in mir.basic_blocks().iter_enumerated() {
+            let (func, receiver) = match data.terminator().kind {
+                TerminatorKind::Call {
+                    ref func,
+                    ref args,
+                    destination: Some(_),
+                    ..
+                } => match args[..] {
+                    [Operand::Move(ref place)] | [Operand::Copy(ref place)] => (func, place),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let (def_id, substs) = match func.ty(mir, tcx).sty {
+                ty::FnDef(def_id, substs) => (def_id, substs),
+                _ => continue,
+            };
+            if tcx.trait_of_item(def_id) != Some(clone_trait) || tcx.item_name(def_id) != "clone" {
+                continue;
+            }
+            let self_ty = substs.type_at(0);
+            let span = data.terminator().source_info.span;
+            if !self_ty.is_copy_modulo_regions(tcx, param_env, span) {
+                continue;
+            }
+            if !clone_is_copy(tcx, param_env, def_id, substs) {
+                continue;
+            }
+            copies.push((bb, receiver.clone().deref()));
+        }
+
+        for (bb, value) in copies {
+            debug!("replacing the clone in {:?} by a copy of {:?}", bb, value);
+            let data = &mut mir[bb];
+            let terminator = data.terminator.take().unwrap();
+            let (dest, target) = match terminator.kind {
+                TerminatorKind::Call { destination: Some((dest, target)), .. } => (dest, target),
+                _ => bug!("replaced terminator is not a call"),
+            };
+            data.statements.push(Statement {
+                source_info: terminator.source_info,
+                kind: StatementKind::Assign(dest, box Rvalue::Use(Operand::Copy(value))),
+            });
+            data.terminator = Some(Terminator {
+                source_info: terminator.source_info,
+                kind: TerminatorKind::Goto { target },
+            });
+        }
+    }
+}
+
+/// Whether the `Clone::clone` that `def_id` and `substs` resolve to is known
+/// to only copy `*self`.
+fn clone_is_copy<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           param_env: ty::ParamEnv<'tcx>,
+                           def_id: DefId,
+                           substs: &'tcx Substs<'tcx>)
+                           -> bool {
+    let instance = match Instance::resolve(tcx, param_env, def_id, substs) {
+        Some(instance) => instance,
+        None => return false,
+    };
+    match instance.def {
+        // The shims of `Copy` types copy.
+        InstanceDef::CloneShim(..) => true,
+        InstanceDef::Item(def_id) => match tcx.impl_of_method(def_id) {
+            Some(impl_did) if tcx.crate_name(impl_did.krate) == "core" => {
+                // Generic impls, e.g. the derived one of `Option<T>`, clone
+                // their fields, with the field type's `clone` that may do
+                // anything. The one of `&T` does not.
+                match tcx.type_of(impl_did).sty {
+                    ty::Ref(..) => true,
+                    _ => tcx.generics_of(impl_did).count() == 0,
+                }
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
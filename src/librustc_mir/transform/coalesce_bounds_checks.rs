@@ -0,0 +1,336 @@
+//! A pass that removes bounds checks implied by an earlier one.
+//!
+//! Indexing emits `assert(Lt(INDEX, LEN))` before every access, so code such
+//! as `a[i] + a[i]` or `a[i + 1]; a[i]` checks the same bound more than once.
+//! Within a straight-line chain of blocks (each block entered only from the
+//! previous one) we remember which `BASE + OFFSET < LEN` facts have been
+//! asserted, and an assertion of `BASE + OFFSET' < LEN'` is replaced by a
+//! `goto` if a known fact has `OFFSET >= OFFSET'` and `LEN <= LEN'`.
+//!
+//! To stay sound:
+//!
+//! * offsets are only tracked through `CheckedAdd`s whose overflow check was
+//!   already passed, as a wrapping addition could make `i + 1 < len` hold
+//!   for an out of bounds `i`;
+//! * locals that are ever borrowed are never tracked, since they could be
+//!   changed behind our back;
+//! * only the lengths of array locals and of `*LOCAL` are tracked, as those
+//!   can only change when `LOCAL` itself is written to;
+//! * everything known about a local is forgotten as soon as it is written
+//!   to, including through a projection.
+
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::fx::FxHashMap;
+use crate::transform::{MirPass, MirSource};
+
+pub struct CoalesceBoundsChecks;
+
+impl MirPass for CoalesceBoundsChecks {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let redundant = find_redundant_checks(tcx, mir);
+        for bb in redundant {
+            let terminator = mir[bb].terminator_mut();
+            let target = match terminator.kind {
+                TerminatorKind::Assert { target, .. } => target,
+                _ => bug!("redundant bounds check is not an `Assert`"),
+            };
+            debug!("removing redundant bounds check in {:?}", bb);
+            terminator.kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// `BASE + OFFSET`, where the addition is known not to overflow.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct Index {
+    base: Local,
+    offset: u128,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Len<'tcx> {
+    /// `Len(PLACE)`.
+    Place(Place<'tcx>),
+    Const(u128),
+}
+
+impl<'tcx> Len<'tcx> {
+    /// Whether `self < other` follows from `self == other` or `self <= other`.
+    fn at_most(&self, other: &Len<'tcx>) -> bool {
+        match (self, other) {
+            (Len::Place(a), Len::Place(b)) => a == b,
+            (Len::Const(a), Len::Const(b)) => a <= b,
+            _ => false,
+        }
+    }
+
+    fn mentions(&self, local: Local) -> bool {
+        match *self {
+            Len::Place(ref place) => place_mentions(place, local),
+            Len::Const(_) => false,
+        }
+    }
+}
+
+/// What is known at some point of a chain of blocks.
+#[derive(Clone, Default)]
+struct State<'tcx> {
+    /// Locals known to hold an index.
+    indices: FxHashMap<Local, Index>,
+    /// Locals known to hold a length.
+    lens: FxHashMap<Local, Len<'tcx>>,
+    /// Locals holding the result of `Lt(INDEX, LEN)`.
+    conds: FxHashMap<Local, (Index, Len<'tcx>)>,
+    /// Locals holding a `CheckedAdd` of an index and a constant, and whether
+    /// its overflow check was passed.
+    checked_adds: FxHashMap<Local, (Index, bool)>,
+    /// Asserted facts `INDEX < LEN`.
+    facts: Vec<(Index, Len<'tcx>)>,
+}
+
+impl<'tcx> State<'tcx> {
+    fn forget(&mut self, local: Local) {
+        self.indices.remove(&local);
+        self.indices.retain(|_, index| index.base != local);
+        self.lens.remove(&local);
+        self.lens.retain(|_, len| !len.mentions(local));
+        self.conds.remove(&local);
+        self.conds.retain(|_, (index, len)| index.base != local && !len.mentions(local));
+        self.checked_adds.remove(&local);
+        self.checked_adds.retain(|_, (index, _)| index.base != local);
+        self.facts.retain(|(index, len)| index.base != local && !len.mentions(local));
+    }
+
+    fn index(&self, operand: &Operand<'tcx>, borrowed: &BitSet<Local>) -> Option<Index> {
+        match *operand {
+            Operand::Copy(Place::Local(local)) | Operand::Move(Place::Local(local)) => {
+                if let Some(&index) = self.indices.get(&local) {
+                    Some(index)
+                } else if !borrowed.contains(local) {
+                    Some(Index { base: local, offset: 0 })
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn len<'a>(&self,
+               tcx: TyCtxt<'a, 'tcx, 'tcx>,
+               operand: &Operand<'tcx>)
+               -> Option<Len<'tcx>> {
+        match *operand {
+            Operand::Copy(Place::Local(local)) | Operand::Move(Place::Local(local)) => {
+                self.lens.get(&local).cloned()
+            }
+            Operand::Constant(ref c) if c.ty == tcx.types.usize => {
+                c.literal.assert_usize(tcx).map(|len| Len::Const(len as u128))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_implied(&self, index: Index, len: &Len<'tcx>) -> bool {
+        self.facts.iter().any(|(known_index, known_len)| {
+            known_index.base == index.base &&
+                known_index.offset >= index.offset &&
+                known_len.at_most(len)
+        })
+    }
+
+    fn statement<'a>(&mut self,
+                     tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                     statement: &Statement<'tcx>,
+                     location: Location,
+                     borrowed: &BitSet<Local>) {
+        let mut written = WrittenLocals { locals: vec![] };
+        written.visit_statement(location.block, statement, location);
+        for local in written.locals {
+            self.forget(local);
+        }
+
+        let (dest, rvalue) = match statement.kind {
+            StatementKind::Assign(Place::Local(dest), box ref rvalue) => (dest, rvalue),
+            _ => return,
+        };
+        if borrowed.contains(dest) {
+            return;
+        }
+        match *rvalue {
+            Rvalue::Use(Operand::Copy(Place::Projection(box ref proj))) |
+            Rvalue::Use(Operand::Move(Place::Projection(box ref proj))) => {
+                // The result of a `CheckedAdd` that is known not to overflow.
+                if let (&Place::Local(sum), &ProjectionElem::Field(field, _)) =
+                    (&proj.base, &proj.elem) {
+                    if let Some(&(index, true)) = self.checked_adds.get(&sum) {
+                        if field.index() == 0 {
+                            self.indices.insert(dest, index);
+                        }
+                    }
+                }
+            }
+            Rvalue::Use(ref operand) => {
+                if let Some(len) = self.len(tcx, operand) {
+                    self.lens.insert(dest, len);
+                } else if let Some(index) = self.index(operand, borrowed) {
+                    self.indices.insert(dest, index);
+                }
+            }
+            Rvalue::Len(ref place) => {
+                let base = match *place {
+                    Place::Local(local) => Some(local),
+                    Place::Projection(box Projection {
+                        base: Place::Local(local),
+                        elem: ProjectionElem::Deref,
+                    }) => Some(local),
+                    _ => None,
+                };
+                if let Some(base) = base {
+                    if !borrowed.contains(base) {
+                        self.lens.insert(dest, Len::Place(place.clone()));
+                    }
+                }
+            }
+            Rvalue::BinaryOp(BinOp::Lt, ref lhs, ref rhs) => {
+                if let (Some(index), Some(len)) = (self.index(lhs, borrowed), self.len(tcx, rhs)) {
+                    self.conds.insert(dest, (index, len));
+                }
+            }
+            Rvalue::CheckedBinaryOp(BinOp::Add, ref lhs, Operand::Constant(ref c))
+                if c.ty == tcx.types.usize => {
+                if let (Some(index), Some(k)) = (self.index(lhs, borrowed),
+                                                 c.literal.assert_usize(tcx)) {
+                    let index = Index { base: index.base, offset: index.offset + k as u128 };
+                    self.checked_adds.insert(dest, (index, false));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the blocks whose terminator is a bounds check implied by an
+/// earlier one.
+fn find_redundant_checks<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   mir: &Mir<'tcx>)
+                                   -> Vec<BasicBlock> {
+    let mut borrowed = BorrowedLocals(BitSet::new_empty(mir.local_decls.len()));
+    borrowed.visit_mir(mir);
+    let borrowed = borrowed.0;
+
+    // The state on entry to blocks that are only entered from the block
+    // before them in a chain, keyed by the block being entered. Facts only
+    // hold on the edge to an `Assert`'s target, not on its unwind edge.
+    let predecessors = mir.predecessors();
+    let mut entry_states: FxHashMap<BasicBlock, State<'tcx>> = FxHashMap::default();
+    let mut redundant = vec![];
+
+    for (bb, data) in traversal::reverse_postorder(mir) {
+        let mut state = match predecessors[bb][..] {
+            [_] => entry_states.remove(&bb).unwrap_or_default(),
+            _ => State::default(),
+        };
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            let location = Location { block: bb, statement_index };
+            state.statement(tcx, statement, location, &borrowed);
+        }
+
+        let target = match data.terminator().kind {
+            TerminatorKind::Assert { cond: Operand::Copy(Place::Local(cond)),
+                                     expected: true, target, .. } |
+            TerminatorKind::Assert { cond: Operand::Move(Place::Local(cond)),
+                                     expected: true, target, .. } => {
+                if let Some((index, len)) = state.conds.get(&cond).cloned() {
+                    if state.is_implied(index, &len) {
+                        redundant.push(bb);
+                    } else {
+                        state.facts.push((index, len));
+                    }
+                }
+                Some(target)
+            }
+            TerminatorKind::Assert { cond: Operand::Copy(Place::Projection(box ref proj)),
+                                     expected: false, target, .. } |
+            TerminatorKind::Assert { cond: Operand::Move(Place::Projection(box ref proj)),
+                                     expected: false, target, .. } => {
+                // Passing the overflow check of a `CheckedAdd` makes its
+                // result usable as an index.
+                if let (&Place::Local(sum), &ProjectionElem::Field(field, _)) =
+                    (&proj.base, &proj.elem) {
+                    if field.index() == 1 {
+                        if let Some(checked_add) = state.checked_adds.get_mut(&sum) {
+                            checked_add.1 = true;
+                        }
+                    }
+                }
+                Some(target)
+            }
+            TerminatorKind::Assert { target, .. } |
+            TerminatorKind::Goto { target } => Some(target),
+            _ => None,
+        };
+        if let Some(target) = target {
+            if predecessors[target].len() == 1 {
+                entry_states.insert(target, state);
+            }
+        }
+    }
+
+    redundant
+}
+
+fn place_mentions(place: &Place<'_>, local: Local) -> bool {
+    match *place {
+        Place::Local(l) => l == local,
+        Place::Projection(ref proj) => {
+            proj.elem == ProjectionElem::Index(local) || place_mentions(&proj.base, local)
+        }
+        Place::Promoted(_) | Place::Static(_) => false,
+    }
+}
+
+/// Collects the locals a statement writes to or ends the storage of.
+struct WrittenLocals {
+    locals: Vec<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for WrittenLocals {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        if context.is_mutating_use() || context.is_storage_marker() {
+            self.locals.push(local);
+        }
+    }
+}
+
+/// Collects the locals that are (partly) borrowed anywhere in the body.
+struct BorrowedLocals(BitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        if context.is_borrow() {
+            let mut base = place;
+            while let Place::Projection(ref proj) = *base {
+                base = &proj.base;
+            }
+            if let Place::Local(local) = *base {
+                self.0.insert(local);
+            }
+        }
+        self.super_place(place, context, location);
+    }
+}
@@ -0,0 +1,401 @@
+//! A pass that replaces element-by-element copy loops over fixed-size arrays
+//! with a single assignment of the whole array.
+//!
+//! We look for loops of the shape produced by
+//!
+//!     let mut i = 0;
+//!     while i < N {
+//!         dst[i] = src[i];
+//!         i += 1;
+//!     }
+//!
+//! where `dst` and `src` are distinct locals of type `[T; N]` and `N` is a
+//! constant. The loop header must only compute `i < N`, and the body may only
+//! contain the element copy, the bounds checks of the two index expressions,
+//! and the increment of `i` (with or without its overflow check). Such a loop
+//! is replaced by
+//!
+//!     dst = src;
+//!     i = N;
+//!
+//! in the block that enters the loop. Every other assertion in the body would
+//! be observable, so any statement or terminator we do not recognize makes us
+//! leave the loop alone, as does a loop counter that is borrowed or a
+//! temporary that is used outside the loop.
+
+use rustc::mir::*;
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use crate::transform::{MirPass, MirSource};
+use crate::transform::simplify::remove_dead_blocks;
+use crate::util::def_use::DefUseAnalysis;
+
+pub struct CollapseCopyLoops;
+
+impl MirPass for CollapseCopyLoops {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let mut def_use_analysis = DefUseAnalysis::new(mir);
+        def_use_analysis.analyze(mir);
+
+        // The blocks of two recognized loops are always disjoint, and the
+        // rewrite only touches the block entering each loop, so all loops can
+        // be found before any of them is collapsed.
+        let loops: Vec<_> = mir.basic_blocks().indices().filter_map(|header| {
+            find_copy_loop(tcx, mir, &def_use_analysis, header)
+        }).collect();
+        if loops.is_empty() {
+            return;
+        }
+
+        for copy_loop in loops {
+            collapse(tcx, mir, copy_loop);
+        }
+        remove_dead_blocks(mir);
+    }
+}
+
+struct CopyLoop {
+    /// The block jumping into the loop header.
+    preheader: BasicBlock,
+    /// The block the loop header branches to once the counter reaches `len`.
+    exit: BasicBlock,
+    counter: Local,
+    len: u64,
+    dst: Local,
+    src: Local,
+    source_info: SourceInfo,
+}
+
+fn find_copy_loop<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                            mir: &Mir<'tcx>,
+                            def_use_analysis: &DefUseAnalysis<'tcx>,
+                            header: BasicBlock)
+                            -> Option<CopyLoop> {
+    let (cond, exit, body) = match mir[header].terminator().kind {
+        TerminatorKind::SwitchInt { discr: Operand::Copy(Place::Local(cond)),
+                                    ref values, ref targets, .. } |
+        TerminatorKind::SwitchInt { discr: Operand::Move(Place::Local(cond)),
+                                    ref values, ref targets, .. }
+            if values[..] == [0] && targets.len() == 2 => (cond, targets[0], targets[1]),
+        _ => return None,
+    };
+
+    // The loop counter and trip count come from the `Lt` feeding the switch.
+    let (counter, len) = mir[header].statements.iter().rev().find_map(|statement| {
+        match statement.kind {
+            StatementKind::Assign(Place::Local(dest), box Rvalue::BinaryOp(BinOp::Lt,
+                                                                         ref lhs,
+                                                                         ref rhs))
+                if dest == cond => Some((lhs, rhs)),
+            _ => None,
+        }
+    }).and_then(|(lhs, rhs)| {
+        let counter = operand_local(lhs)?;
+        // Look through a copy of the counter into a temporary.
+        let counter = mir[header].statements.iter().find_map(|statement| {
+            match statement.kind {
+                StatementKind::Assign(Place::Local(dest), box Rvalue::Use(ref operand))
+                    if dest == counter => operand_local(operand),
+                _ => None,
+            }
+        }).unwrap_or(counter);
+        Some((counter, constant_usize(tcx, rhs)?))
+    })?;
+    if mir.local_decls[counter].ty != tcx.types.usize {
+        return None;
+    }
+
+    // The body must be a chain of blocks, each only entered from the
+    // previous one, that ends by jumping back to the header.
+    let predecessors = mir.predecessors();
+    let mut blocks = vec![header];
+    let mut prev = header;
+    let mut bb = body;
+    loop {
+        if blocks.contains(&bb) || predecessors[bb][..] != [prev] || mir[bb].is_cleanup {
+            return None;
+        }
+        blocks.push(bb);
+        match mir[bb].terminator().kind {
+            TerminatorKind::Assert { target, .. } => {
+                prev = bb;
+                bb = target;
+            }
+            TerminatorKind::Goto { target } if target == header => break,
+            _ => return None,
+        }
+    }
+    let latch = bb;
+    if blocks.contains(&exit) {
+        return None;
+    }
+
+    if predecessors[header].len() != 2 {
+        return None;
+    }
+    let preheader = *predecessors[header].iter().find(|&&pred| pred != latch)?;
+    match mir[preheader].terminator().kind {
+        TerminatorKind::Goto { .. } => {}
+        _ => return None,
+    }
+    // The last write to the counter before entering the loop must reset it.
+    let init = mir[preheader].statements.iter().rev().find_map(|statement| {
+        match statement.kind {
+            StatementKind::Assign(Place::Local(dest), box Rvalue::Use(ref operand))
+                if dest == counter => Some(constant_usize(tcx, operand)),
+            StatementKind::Assign(Place::Local(dest), _) if dest == counter => Some(None),
+            _ => None,
+        }
+    });
+    if init != Some(Some(0)) {
+        return None;
+    }
+
+    let mut matcher = BodyMatcher {
+        tcx,
+        counter,
+        len,
+        incremented: false,
+        index_temps: vec![],
+        constants: FxHashMap::default(),
+        bounds_checks: vec![],
+        checked_step: None,
+        copy: None,
+        temps: vec![],
+    };
+    for &bb in &blocks {
+        for statement in &mir[bb].statements {
+            matcher.statement(mir, statement)?;
+        }
+        if bb != header && bb != latch {
+            matcher.assert(&mir[bb].terminator().kind)?;
+        }
+    }
+    let (dst, src, source_info) = matcher.copy?;
+    if !matcher.incremented || !matcher.bounds_checks.contains(&cond) {
+        return None;
+    }
+    for &array in &[dst, src] {
+        match mir.local_decls[array].ty.sty {
+            ty::Array(_, array_len) if array_len.assert_usize(tcx) == Some(len) => {}
+            _ => return None,
+        }
+    }
+
+    // The loop can only go away if nothing outside of it observes the
+    // counter's intermediate values or the temporaries computed in the body.
+    if def_use_analysis.local_info(counter).defs_and_uses.iter().any(|place_use| {
+        place_use.context.is_borrow()
+    }) {
+        return None;
+    }
+    for &temp in &matcher.temps {
+        if def_use_analysis.local_info(temp).defs_and_uses.iter().any(|place_use| {
+            place_use.context.is_use() && !blocks.contains(&place_use.location.block)
+        }) {
+            return None;
+        }
+    }
+
+    debug!("collapsing copy loop {:?} from {:?} to {:?} ({} elements)",
+           header, src, dst, len);
+    Some(CopyLoop { preheader, exit, counter, len, dst, src, source_info })
+}
+
+/// Checks the statements of the loop in execution order, collecting the
+/// temporaries that hold the counter, constants and bounds checks.
+struct BodyMatcher<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    counter: Local,
+    len: u64,
+    /// Whether the counter was already incremented in this iteration.
+    incremented: bool,
+    /// Temporaries holding the value of the counter before the increment.
+    index_temps: Vec<Local>,
+    /// Temporaries holding a `usize` constant, such as an array length.
+    constants: FxHashMap<Local, u64>,
+    /// Temporaries holding `index < bound` for a `bound` of at least `len`.
+    bounds_checks: Vec<Local>,
+    /// The temporary holding the result of `CheckedAdd(counter, 1)`.
+    checked_step: Option<Local>,
+    copy: Option<(Local, Local, SourceInfo)>,
+    /// Every temporary assigned in the loop.
+    temps: Vec<Local>,
+}
+
+impl<'a, 'tcx> BodyMatcher<'a, 'tcx> {
+    fn statement(&mut self, mir: &Mir<'tcx>, statement: &Statement<'tcx>) -> Option<()> {
+        let (place, rvalue) = match statement.kind {
+            StatementKind::StorageLive(local) |
+            StatementKind::StorageDead(local) if local != self.counter => return Some(()),
+            StatementKind::Nop => return Some(()),
+            StatementKind::Assign(ref place, box ref rvalue) => (place, rvalue),
+            _ => return None,
+        };
+
+        match *place {
+            Place::Local(local) if local == self.counter => {
+                if self.incremented {
+                    return None;
+                }
+                self.incremented = match *rvalue {
+                    Rvalue::Use(Operand::Copy(Place::Projection(box ref proj))) |
+                    Rvalue::Use(Operand::Move(Place::Projection(box ref proj))) => {
+                        match (&proj.base, &proj.elem) {
+                            (&Place::Local(step), &ProjectionElem::Field(field, _)) => {
+                                Some(step) == self.checked_step && field.index() == 0
+                            }
+                            _ => false,
+                        }
+                    }
+                    Rvalue::BinaryOp(BinOp::Add, ref lhs, ref rhs) => self.is_step(lhs, rhs),
+                    _ => false,
+                };
+                if self.incremented { Some(()) } else { None }
+            }
+            Place::Local(temp) => {
+                if mir.local_kind(temp) != LocalKind::Temp || self.temps.contains(&temp) {
+                    return None;
+                }
+                self.temps.push(temp);
+                match *rvalue {
+                    Rvalue::Use(ref operand) if self.is_index(operand) => {
+                        self.index_temps.push(temp);
+                    }
+                    Rvalue::Use(ref operand) => {
+                        let value = constant_usize(self.tcx, operand)?;
+                        self.constants.insert(temp, value);
+                    }
+                    Rvalue::Len(Place::Local(array)) => {
+                        let value = match mir.local_decls[array].ty.sty {
+                            ty::Array(_, len) => len.assert_usize(self.tcx)?,
+                            _ => return None,
+                        };
+                        self.constants.insert(temp, value);
+                    }
+                    Rvalue::BinaryOp(BinOp::Lt, ref lhs, ref rhs) if self.is_index(lhs) => {
+                        if self.bound(rhs)? < self.len {
+                            return None;
+                        }
+                        self.bounds_checks.push(temp);
+                    }
+                    Rvalue::CheckedBinaryOp(BinOp::Add, ref lhs, ref rhs)
+                        if self.checked_step.is_none() && self.is_step(lhs, rhs) => {
+                        self.checked_step = Some(temp);
+                    }
+                    _ => return None,
+                }
+                Some(())
+            }
+            Place::Projection(box ref dst) if self.copy.is_none() => {
+                let src = match *rvalue {
+                    Rvalue::Use(Operand::Copy(Place::Projection(box ref src))) => src,
+                    _ => return None,
+                };
+                let dst_local = self.indexed_local(dst)?;
+                let src_local = self.indexed_local(src)?;
+                if dst_local == src_local {
+                    return None;
+                }
+                self.copy = Some((dst_local, src_local, statement.source_info));
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    /// Only assertions that can never fail inside the loop are allowed.
+    fn assert(&self, terminator: &TerminatorKind<'tcx>) -> Option<()> {
+        match *terminator {
+            TerminatorKind::Assert { cond: Operand::Copy(Place::Local(cond)), expected: true, .. } |
+            TerminatorKind::Assert { cond: Operand::Move(Place::Local(cond)), expected: true, .. }
+                if self.bounds_checks.contains(&cond) => Some(()),
+            TerminatorKind::Assert { cond: Operand::Copy(Place::Projection(box ref proj)),
+                                     expected: false, .. } |
+            TerminatorKind::Assert { cond: Operand::Move(Place::Projection(box ref proj)),
+                                     expected: false, .. } => {
+                // The overflow check of the increment, which cannot fire as
+                // the counter stays below the length of an array.
+                match (&proj.base, &proj.elem) {
+                    (&Place::Local(step), &ProjectionElem::Field(field, _))
+                        if Some(step) == self.checked_step && field.index() == 1 => Some(()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn is_index(&self, operand: &Operand<'tcx>) -> bool {
+        operand_local(operand).map_or(false, |local| self.is_index_local(local))
+    }
+
+    fn is_index_local(&self, local: Local) -> bool {
+        (local == self.counter && !self.incremented) || self.index_temps.contains(&local)
+    }
+
+    fn is_step(&self, lhs: &Operand<'tcx>, rhs: &Operand<'tcx>) -> bool {
+        self.is_index(lhs) && constant_usize(self.tcx, rhs) == Some(1)
+    }
+
+    fn bound(&self, operand: &Operand<'tcx>) -> Option<u64> {
+        match operand_local(operand) {
+            Some(local) => self.constants.get(&local).cloned(),
+            None => constant_usize(self.tcx, operand),
+        }
+    }
+
+    /// Returns `local` if `place` is `local[index]` for an index holding the
+    /// value of the counter.
+    fn indexed_local(&self, place: &PlaceProjection<'tcx>) -> Option<Local> {
+        match (&place.base, &place.elem) {
+            (&Place::Local(local), &ProjectionElem::Index(index))
+                if self.is_index_local(index) => Some(local),
+            _ => None,
+        }
+    }
+}
+
+fn collapse<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir: &mut Mir<'tcx>, copy_loop: CopyLoop) {
+    let CopyLoop { preheader, exit, counter, len, dst, src, source_info } = copy_loop;
+    let final_count = Operand::Constant(box Constant {
+        span: source_info.span,
+        ty: tcx.types.usize,
+        user_ty: None,
+        literal: tcx.mk_lazy_const(ty::LazyConst::Evaluated(ty::Const::from_usize(tcx, len))),
+    });
+
+    let block = &mut mir[preheader];
+    block.statements.push(Statement {
+        source_info,
+        kind: StatementKind::Assign(Place::Local(dst),
+                                    box Rvalue::Use(Operand::Copy(Place::Local(src)))),
+    });
+    block.statements.push(Statement {
+        source_info,
+        kind: StatementKind::Assign(Place::Local(counter), box Rvalue::Use(final_count)),
+    });
+    block.terminator_mut().kind = TerminatorKind::Goto { target: exit };
+}
+
+fn operand_local(operand: &Operand<'_>) -> Option<Local> {
+    match *operand {
+        Operand::Copy(Place::Local(local)) | Operand::Move(Place::Local(local)) => Some(local),
+        _ => None,
+    }
+}
+
+fn constant_usize<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, operand: &Operand<'tcx>) -> Option<u64> {
+    match *operand {
+        Operand::Constant(ref c) if c.ty == tcx.types.usize => c.literal.assert_usize(tcx),
+        _ => None,
+    }
+}
@@ -0,0 +1,199 @@
+//! A pass that replaces unsigned division by a constant with a multiplication
+//! and shifts.
+//!
+//! For an `N`-bit unsigned `x` and a constant `d` that is not a power of two,
+//! let `l = ceil(log2(d))` and `m = floor(2^(N + l) / d) + 1`. Then
+//! `x / d == (x * m) >> (N + l)` for every `x` (Granlund and Montgomery,
+//! "Division by Invariant Integers using Multiplication"). `m` needs `N + 1`
+//! bits, so it is split into `2^N + M` and the division becomes
+//!
+//!     X = SRC
+//!     T = ((X as W) * M >> N) as U
+//!     DEST = ((X - T) >> 1 + T) >> (l - 1)
+//!
+//! where `W` is twice as wide as `U`. None of these operations can overflow.
+//!
+//! Signed division rounds towards zero and needs extra corrections for
+//! negative dividends, so it is left alone, as are divisions by powers of
+//! two (which are cheaper as a single shift) and types without a wider
+//! unsigned type to multiply in.
+
+use rustc::mir::*;
+use rustc::ty::{self, Ty, TyCtxt, ParamEnv};
+use syntax::ast::UintTy;
+use syntax_pos::Span;
+use crate::transform::{MirPass, MirSource};
+use crate::util::patch::MirPatch;
+
+pub struct DivisionByConstant;
+
+impl MirPass for DivisionByConstant {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let mut candidates = vec![];
+        for (block, data) in mir.basic_blocks().iter_enumerated() {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let (ty, divisor) = match statement.kind {
+                    StatementKind::Assign(_, box Rvalue::BinaryOp(BinOp::Div,
+                                                                  ref lhs,
+                                                                  ref rhs)) => {
+                        let ty = lhs.ty(mir, tcx);
+                        match constant_bits(tcx, rhs, ty) {
+                            Some(divisor) => (ty, divisor),
+                            None => continue,
+                        }
+                    }
+                    _ => continue,
+                };
+                if let Some(magic) = Magic::new(tcx, ty, divisor) {
+                    candidates.push((Location { block, statement_index }, magic));
+                }
+            }
+        }
+        if candidates.is_empty() {
+            return;
+        }
+
+        let mut patch = MirPatch::new(mir);
+        for (location, magic) in candidates {
+            rewrite(tcx, mir, &mut patch, location, magic);
+        }
+        patch.apply(mir);
+    }
+}
+
+struct Magic<'tcx> {
+    ty: Ty<'tcx>,
+    wide_ty: Ty<'tcx>,
+    bits: u64,
+    /// The low `bits` bits of the multiplier.
+    multiplier: u128,
+    /// `ceil(log2(divisor))`.
+    log2: u32,
+}
+
+impl<'tcx> Magic<'tcx> {
+    fn new<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>, ty: Ty<'tcx>, divisor: u128) -> Option<Self> {
+        let uint_ty = match ty.sty {
+            ty::Uint(uint_ty) => uint_ty,
+            _ => return None,
+        };
+        let bits = match uint_ty {
+            UintTy::U8 => 8,
+            UintTy::U16 => 16,
+            UintTy::U32 => 32,
+            UintTy::U64 => 64,
+            UintTy::Usize => tcx.data_layout.pointer_size.bits(),
+            UintTy::U128 => return None,
+        };
+        let wide_ty = match bits {
+            8 => tcx.types.u16,
+            16 => tcx.types.u32,
+            32 => tcx.types.u64,
+            64 => tcx.types.u128,
+            _ => return None,
+        };
+        // Dividing by zero panics, and powers of two are better off as shifts.
+        if divisor == 0 || divisor.is_power_of_two() {
+            return None;
+        }
+
+        let log2 = 128 - (divisor - 1).leading_zeros();
+        // `floor(2^(bits + log2) / divisor) + 1 - 2^bits`, computed without
+        // overflowing `u128`: `2^(bits + log2) = 2^bits * (divisor * q + r)`.
+        let pow = 1u128 << log2;
+        let (q, r) = (pow / divisor, pow % divisor);
+        let multiplier = ((q << bits) + (r << bits) / divisor + 1) - (1 << bits);
+        debug_assert!(multiplier < 1 << bits);
+        Some(Magic { ty, wide_ty, bits, multiplier, log2 })
+    }
+}
+
+fn rewrite<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                     mir: &mut Mir<'tcx>,
+                     patch: &mut MirPatch<'tcx>,
+                     location: Location,
+                     magic: Magic<'tcx>) {
+    let Magic { ty, wide_ty, bits, multiplier, log2 } = magic;
+    let statement = &mut mir[location.block].statements[location.statement_index];
+    let span = statement.source_info.span;
+    let (op, lhs, rhs) = match statement.kind {
+        StatementKind::Assign(_, box Rvalue::BinaryOp(ref mut op, ref mut lhs, ref mut rhs))
+            if *op == BinOp::Div => (op, lhs, rhs),
+        _ => bug!("rewritten statement is not a `Div`"),
+    };
+    debug!("replacing division by {:?} at {:?}", rhs, location);
+
+    let x = patch.new_temp(ty, span);
+    let x_wide = patch.new_temp(wide_ty, span);
+    let product = patch.new_temp(wide_ty, span);
+    let high_wide = patch.new_temp(wide_ty, span);
+    let high = patch.new_temp(ty, span);
+    let diff = patch.new_temp(ty, span);
+    let half = patch.new_temp(ty, span);
+    let sum = patch.new_temp(ty, span);
+
+    // What is left of the original statement is `DEST = Shr(move SUM, const (l - 1))`.
+    let src = std::mem::replace(lhs, Operand::Move(Place::Local(sum)));
+    *op = BinOp::Shr;
+    *rhs = constant(tcx, ty, (log2 - 1) as u128, span);
+
+    let copy = |local| Operand::Copy(Place::Local(local));
+    let move_ = |local| Operand::Move(Place::Local(local));
+    patch.add_assign(location, Place::Local(x), Rvalue::Use(src));
+    patch.add_assign(location, Place::Local(x_wide),
+                     Rvalue::Cast(CastKind::Misc, copy(x), wide_ty));
+    patch.add_assign(location, Place::Local(product),
+                     Rvalue::BinaryOp(BinOp::Mul,
+                                      move_(x_wide),
+                                      constant(tcx, wide_ty, multiplier, span)));
+    patch.add_assign(location, Place::Local(high_wide),
+                     Rvalue::BinaryOp(BinOp::Shr,
+                                      move_(product),
+                                      constant(tcx, wide_ty, bits as u128, span)));
+    patch.add_assign(location, Place::Local(high),
+                     Rvalue::Cast(CastKind::Misc, move_(high_wide), ty));
+    patch.add_assign(location, Place::Local(diff),
+                     Rvalue::BinaryOp(BinOp::Sub, copy(x), copy(high)));
+    patch.add_assign(location, Place::Local(half),
+                     Rvalue::BinaryOp(BinOp::Shr, move_(diff), constant(tcx, ty, 1, span)));
+    patch.add_assign(location, Place::Local(sum),
+                     Rvalue::BinaryOp(BinOp::Add, move_(half), copy(high)));
+}
+
+/// Returns the value of `operand` if it is a constant of type `ty`.
+fn constant_bits<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           operand: &Operand<'tcx>,
+                           ty: Ty<'tcx>)
+                           -> Option<u128> {
+    match *operand {
+        Operand::Constant(ref c) if c.ty == ty => {
+            c.literal.map_evaluated(|c| c.to_bits(tcx, ParamEnv::empty().and(ty)))
+        }
+        _ => None,
+    }
+}
+
+fn constant<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                      ty: Ty<'tcx>,
+                      bits: u128,
+                      span: Span)
+                      -> Operand<'tcx> {
+    Operand::Constant(box Constant {
+        span,
+        ty,
+        user_ty: None,
+        literal: tcx.mk_lazy_const(ty::LazyConst::Evaluated(ty::Const::from_bits(
+            tcx,
+            bits,
+            ParamEnv::empty().and(ty),
+        ))),
+    })
+}
@@ -0,0 +1,155 @@
+//! A pass that folds `max` and `min` of two constants.
+//!
+//! `cmp::max(a, b)`, `cmp::min(a, b)`, `a.max(b)` and `a.min(b)` on
+//! integers, `char`s, `bool`s and floats, with constant `a` and `b`, are
+//! replaced by an assignment of the argument the call would return.
+//!
+//! The choice follows the library's definitions exactly: `Ord::max` returns
+//! its second argument when both are equal, and the float methods return
+//! the argument that is not NaN. Calls whose result would be a NaN are left
+//! alone, since the library canonicalizes it.
+
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::ty::{self, Ty, TyCtxt, ParamEnv};
+use syntax::ast::FloatTy;
+use crate::transform::{MirPass, MirSource};
+
+pub struct FoldConstMinMax;
+
+#[derive(Copy, Clone, PartialEq)]
+enum MinMax {
+    Min,
+    Max,
+}
+
+impl MirPass for FoldConstMinMax {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when optimizing MIR (at any level).
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return
+        }
+
+        let mut folds = vec![];
+        for (bb, data) in mir.basic_blocks().iter_enumerated() {
+            let (func, args) = match data.terminator().kind {
+                TerminatorKind::Call { ref func, ref args, destination: Some(_), .. }
+                    if args.len() == 2 => (func, args),
+                _ => continue,
+            };
+            let kind = match func.ty(mir, tcx).sty {
+                ty::FnDef(def_id, _) => match min_max_kind(tcx, def_id) {
+                    Some(kind) => kind,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let ty = args[0].ty(mir, tcx);
+            let values = (constant_bits(tcx, ty, &args[0]), constant_bits(tcx, ty, &args[1]));
+            if let (Some(a), Some(b)) = values {
+                if let Some(index) = pick(tcx, ty, kind, a, b) {
+                    folds.push((bb, args[index].clone()));
+                }
+            }
+        }
+
+        for (bb, result) in folds {
+            debug!("folding the call in {:?} to {:?}", bb, result);
+            let data = &mut mir[bb];
+            let terminator = data.terminator.take().unwrap();
+            let (dest, target) = match terminator.kind {
+                TerminatorKind::Call { destination: Some((dest, target)), .. } => (dest, target),
+                _ => bug!("folded terminator is not a call"),
+            };
+            data.statements.push(Statement {
+                source_info: terminator.source_info,
+                kind: StatementKind::Assign(dest, box Rvalue::Use(result)),
+            });
+            data.terminator = Some(Terminator {
+                source_info: terminator.source_info,
+                kind: TerminatorKind::Goto { target },
+            });
+        }
+    }
+}
+
+/// Whether `def_id` is `cmp::max`, `cmp::min`, `Ord::max`, `Ord::min`, or
+/// the `max` or `min` method of a float type.
+fn min_max_kind<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> Option<MinMax> {
+    let name = tcx.item_name(def_id);
+    let kind = if name == "max" {
+        MinMax::Max
+    } else if name == "min" {
+        MinMax::Min
+    } else {
+        return None;
+    };
+    let known = if let Some(trait_did) = tcx.trait_of_item(def_id) {
+        tcx.absolute_item_path_str(trait_did) == "core::cmp::Ord"
+    } else if let Some(impl_did) = tcx.impl_of_method(def_id) {
+        match tcx.type_of(impl_did).sty {
+            ty::Float(_) => true,
+            _ => false,
+        }
+    } else {
+        tcx.absolute_item_path_str(tcx.parent_def_id(def_id)?) == "core::cmp"
+    };
+    if known { Some(kind) } else { None }
+}
+
+fn constant_bits<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           ty: Ty<'tcx>,
+                           operand: &Operand<'tcx>)
+                           -> Option<u128> {
+    match *operand {
+        Operand::Constant(ref c) if c.ty == ty => {
+            c.literal.map_evaluated(|c| c.to_bits(tcx, ParamEnv::empty().and(ty)))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the index of the argument that `max(a, b)` or `min(a, b)`
+/// returns, or `None` if the values cannot be compared here.
+fn pick<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                  ty: Ty<'tcx>,
+                  kind: MinMax,
+                  a: u128,
+                  b: u128)
+                  -> Option<usize> {
+    let a_greater = match ty.sty {
+        ty::Float(fty) => {
+            let (a, b) = match fty {
+                FloatTy::F32 => (f64::from(f32::from_bits(a as u32)),
+                                 f64::from(f32::from_bits(b as u32))),
+                FloatTy::F64 => (f64::from_bits(a as u64), f64::from_bits(b as u64)),
+            };
+            // `(if self.is_nan() || self < other { other } else { self })` for
+            // `max`, and `(if other.is_nan() || self < other { self } else { other })`
+            // for `min`.
+            let (index, result) = match kind {
+                MinMax::Max if a.is_nan() || a < b => (1, b),
+                MinMax::Max => (0, a),
+                MinMax::Min if b.is_nan() || a < b => (0, a),
+                MinMax::Min => (1, b),
+            };
+            return if result.is_nan() { None } else { Some(index) };
+        }
+        ty::Int(ity) => {
+            let bits = ity.bit_width().unwrap_or(tcx.data_layout.pointer_size.bits() as usize);
+            let shift = 128 - bits;
+            ((a << shift) as i128 >> shift) > ((b << shift) as i128 >> shift)
+        }
+        ty::Uint(_) | ty::Char | ty::Bool => a > b,
+        _ => return None,
+    };
+    // `Ord::max` returns `other` and `Ord::min` returns `self` when both are
+    // equal.
+    Some(match kind {
+        MinMax::Max => if a_greater { 0 } else { 1 },
+        MinMax::Min => if a_greater { 1 } else { 0 },
+    })
+}
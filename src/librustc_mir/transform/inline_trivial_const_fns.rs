@@ -0,0 +1,217 @@
+//! A pass that inlines calls to trivial `const fn`s.
+//!
+//! Small `const fn`s such as `const fn double(x: u32) -> u32 { x + x }`
+//! are common, and inlining them early lets constant propagation see
+//! through them. The general inliner only runs at higher optimization
+//! levels and weighs costs; this pass only handles callees that are
+//! trivially worth it:
+//!
+//! * a non-generic `const fn` that is not `#[inline(never)]`;
+//! * whose optimized MIR is a single block ending in `return`;
+//! * with fewer than `MAX_STATEMENTS` statements, all of them assignments
+//!   of arithmetic, casts or copies, or storage markers;
+//! * that does not refer to promoted constants or statics.
+//!
+//! Such a callee cannot call anything, so there is nothing to recurse into.
+//! The call is replaced by assignments of the arguments to fresh locals,
+//! the callee's statements on those locals, and an assignment of the
+//! callee's return place to the call's destination.
+
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::indexed_vec::IndexVec;
+use syntax::attr;
+use crate::transform::{MirPass, MirSource};
+
+const MAX_STATEMENTS: usize = 8;
+
+pub struct InlineTrivialConstFns;
+
+impl MirPass for InlineTrivialConstFns {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+        if source.promoted.is_some() {
+            return;
+        }
+
+        let mut callsites = vec![];
+        for (bb, data) in mir.basic_blocks().iter_enumerated() {
+            if data.is_cleanup {
+                continue;
+            }
+            let callee = match data.terminator().kind {
+                TerminatorKind::Call { ref func, destination: Some(_), .. } => {
+                    match func.ty(mir, tcx).sty {
+                        ty::FnDef(callee, _) => callee,
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+            if let Some(callee_mir) = trivial_callee(tcx, source.def_id(), callee) {
+                callsites.push((bb, callee_mir));
+            }
+        }
+
+        for (bb, callee_mir) in callsites {
+            debug!("inlining trivial const fn {:?} into {:?}", callee_mir.span, bb);
+            inline_call(mir, bb, callee_mir);
+        }
+    }
+}
+
+/// Returns the MIR of `callee` if it is a trivial `const fn` that can be
+/// inlined into `caller`.
+fn trivial_callee<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                            caller: DefId,
+                            callee: DefId)
+                            -> Option<&'tcx Mir<'tcx>> {
+    if callee == caller || !tcx.is_const_fn(callee) || !tcx.is_mir_available(callee) {
+        return None;
+    }
+    if tcx.generics_of(callee).count() != 0 {
+        return None;
+    }
+    if let attr::InlineAttr::Never = tcx.codegen_fn_attrs(callee).inline {
+        debug!("not inlining {:?}: #[inline(never)] present", callee);
+        return None;
+    }
+
+    // As in the general inliner, only ask for the optimized MIR of local
+    // functions with a higher node id, so that two functions can never wait
+    // for each other's MIR. This does not work with incremental compilation.
+    let caller_node_id = tcx.hir().as_local_node_id(caller).unwrap();
+    if let Some(callee_node_id) = tcx.hir().as_local_node_id(callee) {
+        if tcx.dep_graph.is_fully_enabled() ||
+            callee_node_id.as_u32() <= caller_node_id.as_u32() {
+            return None;
+        }
+    }
+    let callee_mir = tcx.optimized_mir(callee);
+
+    if callee_mir.basic_blocks().len() != 1 ||
+        !callee_mir.promoted.is_empty() ||
+        callee_mir.spread_arg.is_some() {
+        return None;
+    }
+    let block = &callee_mir[START_BLOCK];
+    match block.terminator().kind {
+        TerminatorKind::Return => {}
+        _ => return None,
+    }
+    if block.statements.len() >= MAX_STATEMENTS {
+        return None;
+    }
+    let simple = block.statements.iter().all(|statement| match statement.kind {
+        StatementKind::Assign(_, box ref rvalue) => match *rvalue {
+            Rvalue::Use(_) |
+            Rvalue::BinaryOp(..) |
+            Rvalue::CheckedBinaryOp(..) |
+            Rvalue::UnaryOp(..) |
+            Rvalue::Cast(..) => true,
+            _ => false,
+        },
+        StatementKind::StorageLive(_) |
+        StatementKind::StorageDead(_) |
+        StatementKind::Nop => true,
+        _ => false,
+    });
+    if !simple {
+        return None;
+    }
+
+    let mut local_places = OnlyLocalPlaces(true);
+    local_places.visit_mir(callee_mir);
+    if !local_places.0 {
+        return None;
+    }
+    Some(callee_mir)
+}
+
+fn inline_call<'tcx>(mir: &mut Mir<'tcx>, bb: BasicBlock, callee_mir: &Mir<'tcx>) {
+    let terminator = mir[bb].terminator.take().unwrap();
+    let source_info = terminator.source_info;
+    let (args, dest, target) = match terminator.kind {
+        TerminatorKind::Call { args, destination: Some((dest, target)), .. } => {
+            (args, dest, target)
+        }
+        _ => bug!("inlined terminator is not a call"),
+    };
+
+    // Every local of the callee, the return place included, becomes a new
+    // temporary of the caller.
+    let locals: IndexVec<Local, Local> = callee_mir.local_decls.iter().map(|decl| {
+        mir.local_decls.push(LocalDecl::new_temp(decl.ty, source_info.span))
+    }).collect();
+
+    let mut statements = vec![];
+    for (arg, local) in args.into_iter().zip(callee_mir.args_iter()) {
+        statements.push(Statement {
+            source_info,
+            kind: StatementKind::Assign(Place::Local(locals[local]), box Rvalue::Use(arg)),
+        });
+    }
+    for statement in &callee_mir[START_BLOCK].statements {
+        let mut statement = statement.clone();
+        // The callee's scopes mean nothing in the caller.
+        statement.source_info = source_info;
+        RenameLocals { locals: &locals }.visit_statement(START_BLOCK,
+                                                         &mut statement,
+                                                         Location::START);
+        statements.push(statement);
+    }
+    statements.push(Statement {
+        source_info,
+        kind: StatementKind::Assign(dest,
+                                    box Rvalue::Use(Operand::Move(Place::Local(
+                                        locals[RETURN_PLACE])))),
+    });
+
+    let data = &mut mir[bb];
+    data.statements.extend(statements);
+    data.terminator = Some(Terminator { source_info, kind: TerminatorKind::Goto { target } });
+}
+
+/// Checks that a body only refers to its own locals, not to promoted
+/// constants or statics.
+struct OnlyLocalPlaces(bool);
+
+impl<'tcx> Visitor<'tcx> for OnlyLocalPlaces {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        match *place {
+            Place::Promoted(_) | Place::Static(_) => self.0 = false,
+            _ => {}
+        }
+        self.super_place(place, context, location);
+    }
+}
+
+struct RenameLocals<'a> {
+    locals: &'a IndexVec<Local, Local>,
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for RenameLocals<'a> {
+    fn visit_local(&mut self,
+                   local: &mut Local,
+                   _context: PlaceContext<'tcx>,
+                   _location: Location) {
+        *local = self.locals[*local];
+    }
+
+    fn visit_constant(&mut self, constant: &mut Constant<'tcx>, location: Location) {
+        // User type annotations are indices into the callee's table.
+        constant.user_ty = None;
+        self.super_constant(constant, location);
+    }
+}
@@ -0,0 +1,235 @@
+//! A pass that merges identical cleanup blocks.
+//!
+//! Building MIR shares the unwind path between the calls made while the same
+//! values are live, but later passes copy cleanup blocks: every inlined
+//! callee brings its own `resume` and drop blocks, and drop elaboration
+//! builds a separate unwind ladder for each drop it elaborates. This pass
+//! redirects the edges into a cleanup block to the first cleanup block with
+//! the same statements and terminator, and removes the blocks left unused.
+//!
+//! Merging a block can make its predecessors identical, so the pass repeats
+//! until nothing changes. Only blocks made of storage markers, drop flag
+//! updates and `goto`, `switchInt`, `drop`, `resume` or `abort` terminators
+//! are compared, which covers what drop elaboration and the inliner emit on
+//! unwind paths; anything else is left alone.
+
+use rustc::mir::*;
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::indexed_vec::IndexVec;
+use crate::transform::{MirPass, MirSource};
+use crate::transform::simplify::remove_dead_blocks;
+
+pub struct MergeCleanupBlocks;
+
+impl MirPass for MergeCleanupBlocks {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when optimizing MIR (at any level).
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return
+        }
+
+        if merge_cleanup_blocks(mir.basic_blocks_mut()) {
+            remove_dead_blocks(mir);
+        }
+    }
+}
+
+/// Redirects every edge into a cleanup block to the first identical one.
+/// Returns `true` if any edge was changed; the blocks left unused are not
+/// removed.
+fn merge_cleanup_blocks(basic_blocks: &mut IndexVec<BasicBlock, BasicBlockData<'_>>) -> bool {
+    let mut changed = false;
+    // Each round redirects an edge away from at least one block, which
+    // bounds the rounds. Duplicates left without predecessors still match
+    // in later rounds, so a round only counts if it changed an edge.
+    for _ in basic_blocks.indices() {
+        let replacements = match duplicate_cleanup_blocks(basic_blocks) {
+            Some(replacements) => replacements,
+            None => break,
+        };
+        let mut redirected = false;
+        for data in basic_blocks.iter_mut() {
+            for target in data.terminator_mut().successors_mut() {
+                if replacements[*target] != *target {
+                    *target = replacements[*target];
+                    redirected = true;
+                }
+            }
+        }
+        if !redirected {
+            break;
+        }
+        changed = true;
+    }
+    changed
+}
+
+/// Maps every block to the block its predecessors should jump to instead,
+/// or returns `None` if no two cleanup blocks are identical.
+fn duplicate_cleanup_blocks(basic_blocks: &IndexVec<BasicBlock, BasicBlockData<'_>>)
+                            -> Option<IndexVec<BasicBlock, BasicBlock>> {
+    let mut replacements: IndexVec<_, _> = basic_blocks.indices().collect();
+    let mut canonical = FxHashMap::default();
+    let mut found = false;
+    for (bb, data) in basic_blocks.iter_enumerated() {
+        if !data.is_cleanup {
+            continue;
+        }
+        let key = match CleanupBlock::of(data) {
+            Some(key) => key,
+            None => continue,
+        };
+        let first = *canonical.entry(key).or_insert(bb);
+        if first != bb {
+            debug!("merging cleanup block {:?} into {:?}", bb, first);
+            replacements[bb] = first;
+            found = true;
+        }
+    }
+    if found {
+        Some(replacements)
+    } else {
+        None
+    }
+}
+
+/// The parts of a cleanup block that have to match for two blocks to be
+/// merged. Source information is ignored.
+#[derive(PartialEq, Eq, Hash)]
+struct CleanupBlock<'a, 'tcx: 'a> {
+    statements: Vec<CleanupStatement<'a, 'tcx>>,
+    terminator: CleanupTerminator<'a, 'tcx>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum CleanupStatement<'a, 'tcx: 'a> {
+    StorageLive(Local),
+    StorageDead(Local),
+    /// Assigns a constant, as drop elaboration does to update drop flags.
+    SetConst(&'a Place<'tcx>, &'tcx ty::LazyConst<'tcx>),
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum CleanupTerminator<'a, 'tcx: 'a> {
+    Goto(BasicBlock),
+    SwitchInt(&'a Place<'tcx>, &'a [u128], &'a [BasicBlock]),
+    Drop(&'a Place<'tcx>, BasicBlock, Option<BasicBlock>),
+    Resume,
+    Abort,
+}
+
+impl<'a, 'tcx> CleanupBlock<'a, 'tcx> {
+    /// Returns `None` if the block contains anything not compared.
+    fn of(data: &'a BasicBlockData<'tcx>) -> Option<Self> {
+        let mut statements = vec![];
+        for statement in &data.statements {
+            statements.push(match statement.kind {
+                StatementKind::StorageLive(local) => CleanupStatement::StorageLive(local),
+                StatementKind::StorageDead(local) => CleanupStatement::StorageDead(local),
+                StatementKind::Assign(ref place, box Rvalue::Use(Operand::Constant(ref c))) => {
+                    CleanupStatement::SetConst(place, c.literal)
+                }
+                StatementKind::Nop => continue,
+                _ => return None,
+            });
+        }
+        let terminator = match data.terminator().kind {
+            TerminatorKind::Goto { target } => CleanupTerminator::Goto(target),
+            TerminatorKind::SwitchInt {
+                discr: Operand::Copy(ref place),
+                ref values,
+                ref targets,
+                ..
+            } |
+            TerminatorKind::SwitchInt {
+                discr: Operand::Move(ref place),
+                ref values,
+                ref targets,
+                ..
+            } => CleanupTerminator::SwitchInt(place, &values[..], &targets[..]),
+            TerminatorKind::Drop { ref location, target, unwind } => {
+                CleanupTerminator::Drop(location, target, unwind)
+            }
+            TerminatorKind::Resume => CleanupTerminator::Resume,
+            TerminatorKind::Abort => CleanupTerminator::Abort,
+            _ => return None,
+        };
+        Some(CleanupBlock { statements, terminator })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc::mir::*;
+    use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+    use syntax_pos::DUMMY_SP;
+    use super::merge_cleanup_blocks;
+
+    fn block<'tcx>(kind: TerminatorKind<'tcx>, is_cleanup: bool) -> BasicBlockData<'tcx> {
+        let mut data = BasicBlockData::new(Some(Terminator {
+            source_info: SourceInfo { span: DUMMY_SP, scope: OUTERMOST_SOURCE_SCOPE },
+            kind,
+        }));
+        data.is_cleanup = is_cleanup;
+        data
+    }
+
+    fn drop<'tcx>(local: usize, target: usize, unwind: Option<usize>) -> TerminatorKind<'tcx> {
+        TerminatorKind::Drop {
+            location: Place::Local(Local::new(local)),
+            target: BasicBlock::new(target),
+            unwind: unwind.map(BasicBlock::new),
+        }
+    }
+
+    fn unwind_targets(blocks: &IndexVec<BasicBlock, BasicBlockData<'_>>) -> Vec<BasicBlock> {
+        blocks.iter().filter_map(|data| match data.terminator().kind {
+            TerminatorKind::Drop { unwind: Some(unwind), .. } => Some(unwind),
+            _ => None,
+        }).collect()
+    }
+
+    #[test]
+    fn shared_cleanup() {
+        // Three drops on the normal path, each unwinding into its own copy
+        // of `drop(_4) -> resume`.
+        let mut blocks = IndexVec::new();
+        blocks.push(block(drop(1, 1, Some(4)), false));
+        blocks.push(block(drop(2, 2, Some(6)), false));
+        blocks.push(block(drop(3, 3, Some(8)), false));
+        blocks.push(block(TerminatorKind::Return, false));
+        for resume in &[5, 7, 9] {
+            blocks.push(block(drop(4, *resume, None), true));
+            blocks.push(block(TerminatorKind::Resume, true));
+        }
+
+        assert!(merge_cleanup_blocks(&mut blocks));
+        let merged = BasicBlock::new(4);
+        assert_eq!(unwind_targets(&blocks), vec![merged; 3]);
+        assert_eq!(blocks[merged].terminator().successors().collect::<Vec<_>>(),
+                   vec![&BasicBlock::new(5)]);
+    }
+
+    #[test]
+    fn distinct_cleanups() {
+        // The two drops unwind through drops of different locals.
+        let mut blocks = IndexVec::new();
+        blocks.push(block(drop(1, 1, Some(3)), false));
+        blocks.push(block(drop(2, 2, Some(5)), false));
+        blocks.push(block(TerminatorKind::Return, false));
+        blocks.push(block(drop(3, 4, None), true));
+        blocks.push(block(TerminatorKind::Resume, true));
+        blocks.push(block(drop(4, 6, None), true));
+        blocks.push(block(TerminatorKind::Resume, true));
+
+        // Only the `resume` blocks are merged.
+        assert!(merge_cleanup_blocks(&mut blocks));
+        assert_eq!(unwind_targets(&blocks), vec![BasicBlock::new(3), BasicBlock::new(5)]);
+        assert_eq!(blocks[BasicBlock::new(5)].terminator().successors().collect::<Vec<_>>(),
+                   vec![&BasicBlock::new(4)]);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::borrow_check::nll::type_check;
 use crate::build;
+use crate::util::validate_body;
 use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc::mir::{Mir, MirPhase, Promoted};
 use rustc::ty::{TyCtxt, InstanceDef};
@@ -37,6 +38,26 @@ pub mod generator;
 pub mod inline;
 pub mod lower_128bit;
 pub mod uniform_array_move_out;
+pub mod narrow_int_temps;
+pub mod collapse_copy_loops;
+pub mod division_by_constant;
+pub mod coalesce_bounds_checks;
+pub mod purity;
+pub mod inline_trivial_const_fns;
+pub mod simplify_bool_comparisons;
+pub mod remainder_by_power_of_two;
+pub mod remove_bounded_overflow_checks;
+pub mod remove_unneeded_unwind_edges;
+pub mod preallocate_vecs;
+pub mod fold_const_min_max;
+pub mod remove_redundant_retags;
+pub mod clone_to_copy;
+pub mod reorder_statements;
+pub mod tail_call_to_loop;
+pub mod sink_constants;
+pub mod merge_cleanup_blocks;
+
+pub use self::purity::is_pure;
 
 pub(crate) fn provide(providers: &mut Providers<'_>) {
     self::qualify_consts::provide(providers);
@@ -178,6 +199,14 @@ pub fn run_passes(
             pass.run_pass(tcx, source, mir);
             run_hooks(mir, index, true);
 
+            if tcx.sess.opts.debugging_opts.validate_mir {
+                if let Err(errors) = validate_body(mir) {
+                    let messages: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+                    bug!("invalid MIR for {:?} after pass `{}`:\n{}",
+                         source.def_id(), pass.name(), messages.join("\n"));
+                }
+            }
+
             index += 1;
         };
 
@@ -246,6 +275,7 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
         &simplify_branches::SimplifyBranches::new("initial"),
         &remove_noop_landing_pads::RemoveNoopLandingPads,
         &cleanup_post_borrowck::CleanupNonCodegenStatements,
+        &simplify_bool_comparisons::SimplifyBoolComparisons,
 
         &simplify::SimplifyCfg::new("early-opt"),
 
@@ -274,20 +304,39 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
 
         // Optimizations begin.
         &uniform_array_move_out::RestoreSubsliceArrayMoveOut,
+        &preallocate_vecs::PreallocateVecs,
+        &fold_const_min_max::FoldConstMinMax,
+        &clone_to_copy::CloneToCopy,
+        &inline_trivial_const_fns::InlineTrivialConstFns,
         &inline::Inline,
 
         // Lowering generator control-flow and variables
         // has to happen before we do anything else to them.
         &generator::StateTransform,
 
+        // The inliner leaves behind retags of arguments the caller already
+        // retagged.
+        &remove_redundant_retags::RemoveRedundantRetags,
+
         &instcombine::InstCombine,
+        &collapse_copy_loops::CollapseCopyLoops,
         &const_prop::ConstProp,
         &simplify_branches::SimplifyBranches::new("after-const-prop"),
         &deaggregator::Deaggregator,
+        &sink_constants::SinkConstants,
         &copy_prop::CopyPropagation,
+        &narrow_int_temps::NarrowIntTemps,
+        &division_by_constant::DivisionByConstant,
+        &remainder_by_power_of_two::RemainderByPowerOfTwo,
+        &coalesce_bounds_checks::CoalesceBoundsChecks,
+        &remove_bounded_overflow_checks::RemoveBoundedOverflowChecks,
+        &remove_unneeded_unwind_edges::RemoveUnneededUnwindEdges,
+        &tail_call_to_loop::TailCallToLoop,
+        &merge_cleanup_blocks::MergeCleanupBlocks,
         &remove_noop_landing_pads::RemoveNoopLandingPads,
         &simplify::SimplifyCfg::new("final"),
         &simplify::SimplifyLocals,
+        &reorder_statements::ReorderStatements,
 
         &add_call_guards::CriticalCallEdges,
         &dump_mir::Marker("PreCodegen"),
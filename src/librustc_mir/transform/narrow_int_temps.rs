@@ -0,0 +1,220 @@
+//! A pass that narrows integer temporaries which provably fit in a smaller
+//! unsigned type.
+//!
+//! We look for temporaries that have exactly one definition of the form
+//!
+//!     TMP = BitAnd(SRC, const MASK)
+//!
+//! where `MASK` fits in `u8` (or `u16`), and whose uses are all plain copies
+//! or moves. Such a temporary can never hold a value larger than `MASK`, so
+//! its type is narrowed and the mask is applied to the truncated source:
+//!
+//!     NARROW_SRC = SRC as u8
+//!     TMP = BitAnd(move NARROW_SRC, const MASK_u8)
+//!     ...
+//!     WIDE = TMP as u32
+//!     USE(WIDE)
+//!
+//! Truncating before masking is exact because the mask clears every bit that
+//! the truncation drops, and the zero extension at each use restores the
+//! original value. Temporaries that are borrowed, projected from, assigned
+//! more than once, or defined by anything else are left alone.
+
+use rustc::mir::*;
+use rustc::mir::visit::{MutVisitor, PlaceContext, NonMutatingUseContext};
+use rustc::ty::{self, Ty, TyCtxt, ParamEnv};
+use crate::transform::{MirPass, MirSource};
+use crate::util::def_use::DefUseAnalysis;
+use crate::util::patch::MirPatch;
+
+pub struct NarrowIntTemps;
+
+impl MirPass for NarrowIntTemps {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let mut def_use_analysis = DefUseAnalysis::new(mir);
+        def_use_analysis.analyze(mir);
+
+        let candidates: Vec<_> = mir.local_decls.indices().filter_map(|local| {
+            find_candidate(tcx, mir, &def_use_analysis, local)
+        }).collect();
+        if candidates.is_empty() {
+            return;
+        }
+
+        let mut patch = MirPatch::new(mir);
+        for candidate in candidates {
+            narrow(tcx, mir, &mut patch, candidate);
+        }
+        patch.apply(mir);
+    }
+}
+
+struct Candidate<'tcx> {
+    local: Local,
+    wide_ty: Ty<'tcx>,
+    narrow_ty: Ty<'tcx>,
+    /// The location of the single `TMP = BitAnd(..)` definition.
+    def: Location,
+    /// The (deduplicated) locations of all uses.
+    uses: Vec<Location>,
+}
+
+fn find_candidate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                            mir: &Mir<'tcx>,
+                            def_use_analysis: &DefUseAnalysis<'tcx>,
+                            local: Local)
+                            -> Option<Candidate<'tcx>> {
+    if mir.local_kind(local) != LocalKind::Temp {
+        return None;
+    }
+    let wide_ty = mir.local_decls[local].ty;
+    let wide_bits = int_bits(tcx, wide_ty)?;
+
+    let info = def_use_analysis.local_info(local);
+    if info.def_count() != 1 {
+        return None;
+    }
+    let mut def = None;
+    let mut uses = vec![];
+    for place_use in &info.defs_and_uses {
+        match place_use.context {
+            PlaceContext::MutatingUse(_) => def = Some(place_use.location),
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy) |
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::Move) => {
+                if !uses.contains(&place_use.location) {
+                    uses.push(place_use.location);
+                }
+            }
+            PlaceContext::NonUse(_) => {}
+            // Borrows, projections and inspections need the original type.
+            PlaceContext::NonMutatingUse(_) => return None,
+        }
+    }
+    let def = def?;
+    if uses.is_empty() || uses.contains(&def) {
+        return None;
+    }
+
+    let statement = mir[def.block].statements.get(def.statement_index)?;
+    let mask = match statement.kind {
+        StatementKind::Assign(Place::Local(dest), box Rvalue::BinaryOp(BinOp::BitAnd,
+                                                                     ref lhs,
+                                                                     ref rhs))
+            if dest == local => {
+            constant_bits(tcx, rhs, wide_ty).or_else(|| constant_bits(tcx, lhs, wide_ty))?
+        }
+        _ => return None,
+    };
+
+    let narrow_ty = if mask <= u8::max_value() as u128 {
+        tcx.types.u8
+    } else if mask <= u16::max_value() as u128 {
+        tcx.types.u16
+    } else {
+        return None;
+    };
+    if int_bits(tcx, narrow_ty)? >= wide_bits {
+        return None;
+    }
+
+    debug!("narrowing {:?} from {:?} to {:?} (mask {:#x})", local, wide_ty, narrow_ty, mask);
+    Some(Candidate { local, wide_ty, narrow_ty, def, uses })
+}
+
+fn narrow<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                    mir: &mut Mir<'tcx>,
+                    patch: &mut MirPatch<'tcx>,
+                    candidate: Candidate<'tcx>) {
+    let Candidate { local, wide_ty, narrow_ty, def, uses } = candidate;
+    let span = mir.local_decls[local].source_info.span;
+
+    // Rewrite the definition to mask a truncated copy of the source.
+    let narrow_src = patch.new_temp(narrow_ty, span);
+    {
+        let statement = &mut mir[def.block].statements[def.statement_index];
+        let (lhs, rhs) = match statement.kind {
+            StatementKind::Assign(_, box Rvalue::BinaryOp(BinOp::BitAnd,
+                                                          ref mut lhs,
+                                                          ref mut rhs)) => (lhs, rhs),
+            _ => bug!("narrowed definition is not a `BitAnd`"),
+        };
+        let (src, mask) = if constant_bits(tcx, rhs, wide_ty).is_some() {
+            (lhs, rhs)
+        } else {
+            (rhs, lhs)
+        };
+        let mask_bits = constant_bits(tcx, mask, wide_ty).unwrap();
+        let src = std::mem::replace(src, Operand::Move(Place::Local(narrow_src)));
+        *mask = Operand::Constant(box Constant {
+            span,
+            ty: narrow_ty,
+            user_ty: None,
+            literal: tcx.mk_lazy_const(ty::LazyConst::Evaluated(ty::Const::from_bits(
+                tcx,
+                mask_bits,
+                ParamEnv::empty().and(narrow_ty),
+            ))),
+        });
+        patch.add_assign(def,
+                         Place::Local(narrow_src),
+                         Rvalue::Cast(CastKind::Misc, src, narrow_ty));
+    }
+    mir.local_decls[local].ty = narrow_ty;
+
+    // Zero-extend back to the original type right before every use.
+    for location in uses {
+        let wide = patch.new_temp(wide_ty, span);
+        ReplaceLocal { from: local, to: wide }.visit_location(mir, location);
+        patch.add_assign(location,
+                         Place::Local(wide),
+                         Rvalue::Cast(CastKind::Misc,
+                                      Operand::Copy(Place::Local(local)),
+                                      wide_ty));
+    }
+}
+
+/// Returns the width of `ty` in bits if it is a primitive integer type.
+fn int_bits<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, ty: Ty<'tcx>) -> Option<u64> {
+    match ty.sty {
+        ty::Int(_) | ty::Uint(_) => {
+            tcx.layout_of(ParamEnv::reveal_all().and(ty)).ok().map(|layout| layout.size.bits())
+        }
+        _ => None,
+    }
+}
+
+fn constant_bits<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           operand: &Operand<'tcx>,
+                           ty: Ty<'tcx>)
+                           -> Option<u128> {
+    match *operand {
+        Operand::Constant(ref c) => {
+            c.literal.map_evaluated(|c| c.to_bits(tcx, ParamEnv::empty().and(ty)))
+        }
+        _ => None,
+    }
+}
+
+struct ReplaceLocal {
+    from: Local,
+    to: Local,
+}
+
+impl<'tcx> MutVisitor<'tcx> for ReplaceLocal {
+    fn visit_local(&mut self,
+                   local: &mut Local,
+                   _context: PlaceContext<'tcx>,
+                   _location: Location) {
+        if *local == self.from {
+            *local = self.to;
+        }
+    }
+}
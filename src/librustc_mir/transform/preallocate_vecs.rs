@@ -0,0 +1,180 @@
+//! A pass that preallocates vectors that are filled right after their
+//! creation.
+//!
+//! ```rust,ignore
+//! let mut v = Vec::new();
+//! v.push(a);
+//! v.push(b);
+//! v.push(c);
+//! ```
+//!
+//! grows the vector's buffer several times. When the pushes directly follow
+//! the `Vec::new()` call, their number is known, and the call is replaced by
+//! `Vec::with_capacity(3)` so that a single allocation is made.
+//!
+//! Only pushes that are reached from the `Vec::new()` call along a straight
+//! line of blocks are counted: every block must have a single predecessor,
+//! so pushes in loops or behind branches are not. The scan also stops at the
+//! first call that is not a push onto the same vector, and at the first
+//! statement that uses the vector for anything but borrowing it for a push.
+
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::{self, TyCtxt};
+use rustc::ty::subst::Substs;
+use rustc_data_structures::fx::FxHashSet;
+use syntax::symbol::InternedString;
+use crate::transform::{MirPass, MirSource};
+
+pub struct PreallocateVecs;
+
+impl MirPass for PreallocateVecs {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let mut replacements = vec![];
+        for (bb, data) in mir.basic_blocks().iter_enumerated() {
+            let (func, vec, target) = match data.terminator().kind {
+                TerminatorKind::Call {
+                    ref func,
+                    ref args,
+                    destination: Some((Place::Local(vec), target)),
+                    ..
+                } if args.is_empty() => (func, vec, target),
+                _ => continue,
+            };
+            let (new, substs) = match vec_method(tcx, mir, func) {
+                Some((def_id, substs, name)) if name == "new" => (def_id, substs),
+                _ => continue,
+            };
+            let pushes = count_pushes(tcx, mir, vec, substs, target);
+            if pushes == 0 {
+                continue;
+            }
+            let impl_did = tcx.impl_of_method(new).unwrap();
+            let with_capacity = tcx.associated_items(impl_did)
+                .find(|item| item.ident.name == "with_capacity")
+                .map(|item| item.def_id);
+            if let Some(with_capacity) = with_capacity {
+                replacements.push((bb, with_capacity, substs, pushes));
+            }
+        }
+
+        for (bb, with_capacity, substs, pushes) in replacements {
+            debug!("preallocating {} elements in {:?}", pushes, bb);
+            let span = mir[bb].terminator().source_info.span;
+            if let TerminatorKind::Call { ref mut func, ref mut args, .. } =
+                mir[bb].terminator_mut().kind
+            {
+                *func = Operand::function_handle(tcx, with_capacity, substs, span);
+                *args = vec![Operand::Constant(box Constant {
+                    span,
+                    ty: tcx.types.usize,
+                    user_ty: None,
+                    literal: tcx.mk_lazy_const(ty::LazyConst::Evaluated(
+                        ty::Const::from_usize(tcx, pushes),
+                    )),
+                })];
+            }
+        }
+    }
+}
+
+/// Returns the method of `Vec` that `func` refers to, with the substitutions
+/// of the vector type, if it is an inherent method of `Vec`.
+fn vec_method<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                        mir: &Mir<'tcx>,
+                        func: &Operand<'tcx>)
+                        -> Option<(DefId, &'tcx Substs<'tcx>, InternedString)> {
+    let (def_id, substs) = match func.ty(mir, tcx).sty {
+        ty::FnDef(def_id, substs) => (def_id, substs),
+        _ => return None,
+    };
+    let impl_did = tcx.impl_of_method(def_id)?;
+    if tcx.impl_trait_ref(impl_did).is_some() {
+        return None;
+    }
+    match tcx.type_of(impl_did).sty {
+        ty::Adt(adt, _) if tcx.absolute_item_path_str(adt.did) == "alloc::vec::Vec" => {
+            Some((def_id, substs, tcx.item_name(def_id)))
+        }
+        _ => None,
+    }
+}
+
+/// Counts the pushes onto `vec` that follow the block `start` in a straight
+/// line.
+fn count_pushes<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          mir: &Mir<'tcx>,
+                          vec: Local,
+                          substs: &'tcx Substs<'tcx>,
+                          start: BasicBlock)
+                          -> u64 {
+    let predecessors = mir.predecessors();
+    let mut visited = FxHashSet::default();
+    let mut pushes = 0;
+    let mut bb = start;
+    while predecessors[bb].len() == 1 && visited.insert(bb) {
+        let data = &mir[bb];
+        let mut borrows = vec![];
+        for statement in &data.statements {
+            match statement.kind {
+                StatementKind::Assign(Place::Local(local),
+                                      box Rvalue::Ref(_, BorrowKind::Mut { .. },
+                                                      Place::Local(borrowed)))
+                    if borrowed == vec => borrows.push(local),
+                _ if mentions_local(statement, vec) => return pushes,
+                _ => {}
+            }
+        }
+        bb = match data.terminator().kind {
+            TerminatorKind::Goto { target } => target,
+            TerminatorKind::Call { ref func, ref args, destination: Some((_, target)), .. } => {
+                let is_push = match vec_method(tcx, mir, func) {
+                    Some((_, push_substs, name)) => name == "push" && push_substs == substs,
+                    None => false,
+                };
+                let receiver = match args.first() {
+                    Some(&Operand::Move(Place::Local(local))) => Some(local),
+                    _ => None,
+                };
+                if !is_push || !receiver.map_or(false, |local| borrows.contains(&local)) {
+                    return pushes;
+                }
+                pushes += 1;
+                target
+            }
+            _ => return pushes,
+        };
+    }
+    pushes
+}
+
+fn mentions_local<'tcx>(statement: &Statement<'tcx>, local: Local) -> bool {
+    struct MentionsLocal {
+        local: Local,
+        found: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for MentionsLocal {
+        fn visit_local(&mut self,
+                       local: &Local,
+                       _context: PlaceContext<'tcx>,
+                       _location: Location) {
+            if *local == self.local {
+                self.found = true;
+            }
+        }
+    }
+
+    let mut visitor = MentionsLocal { local, found: false };
+    visitor.visit_statement(START_BLOCK, statement, Location::START);
+    visitor.found
+}
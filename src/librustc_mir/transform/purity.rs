@@ -0,0 +1,209 @@
+//! An analysis that determines whether a function is pure, i.e., whether
+//! calling it has no side effects other than panicking. Reads through
+//! pointers, references and from statics are allowed, so two calls with the
+//! same arguments may still return different values if memory changed in
+//! between.
+//!
+//! A function is pure if its optimized MIR
+//!
+//! * does not write through a pointer or reference, or to a static;
+//! * contains no inline assembly, drops, or generator suspensions;
+//! * only calls pure functions and a handful of side effect free
+//!   intrinsics.
+//!
+//! The analysis is conservative: calls through function pointers and trait
+//! objects, calls to functions without MIR (such as foreign functions), and
+//! calls that cannot be resolved to a single function all count as impure.
+//! Recursive functions are handled by first assuming that everything
+//! reachable is pure, then marking functions impure until a fixpoint is
+//! reached.
+
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+
+/// Functions reachable from the one being asked about that we are willing
+/// to look at before giving up and assuming the worst.
+const MAX_FUNCTIONS: usize = 1000;
+
+/// Returns `true` if calling `def_id` is known to have no side effects.
+pub fn is_pure<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> bool {
+    let pure = holds_for_reachable(def_id, |def_id| direct_callees(tcx, def_id));
+    debug!("is_pure({:?}) = {}", def_id, pure);
+    pure
+}
+
+/// Returns `true` if a property holds for `def_id` and for every function
+/// reachable from it. `direct_callees` returns the functions a function
+/// calls, or `None` if its own body already violates the property.
+pub(crate) fn holds_for_reachable<F>(def_id: DefId, mut direct_callees: F) -> bool
+    where F: FnMut(DefId) -> Option<Vec<DefId>>
+{
+    // The direct callees of every reachable function, or `None` for a
+    // function that violates the property by itself.
+    let mut callees: FxHashMap<DefId, Option<Vec<DefId>>> = FxHashMap::default();
+    let mut stack = vec![def_id];
+    while let Some(def_id) = stack.pop() {
+        if callees.contains_key(&def_id) {
+            continue;
+        }
+        if callees.len() == MAX_FUNCTIONS {
+            debug!("holds_for_reachable: giving up after {} functions", MAX_FUNCTIONS);
+            return false;
+        }
+        let direct_callees = direct_callees(def_id);
+        if let Some(ref direct_callees) = direct_callees {
+            stack.extend(direct_callees.iter().cloned());
+        }
+        callees.insert(def_id, direct_callees);
+    }
+
+    let mut violating: FxHashSet<DefId> = callees.iter()
+        .filter(|(_, direct_callees)| direct_callees.is_none())
+        .map(|(&def_id, _)| def_id)
+        .collect();
+    loop {
+        let newly_violating: Vec<DefId> = callees.iter()
+            .filter(|&(def_id, _)| !violating.contains(def_id))
+            .filter(|(_, direct_callees)| {
+                direct_callees.as_ref().unwrap().iter().any(|callee| violating.contains(callee))
+            })
+            .map(|(&def_id, _)| def_id)
+            .collect();
+        if newly_violating.is_empty() {
+            break;
+        }
+        violating.extend(newly_violating);
+    }
+    !violating.contains(&def_id)
+}
+
+/// For unit testing: reports whether each function marked with
+/// `#[rustc_purity]` is pure.
+pub fn test_purity<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    if !tcx.features().rustc_attrs {
+        return;
+    }
+    for def_id in tcx.body_owners() {
+        if tcx.has_attr(def_id, "rustc_purity") {
+            let purity = if is_pure(tcx, def_id) { "pure" } else { "impure" };
+            tcx.sess.span_err(tcx.def_span(def_id), purity);
+        }
+    }
+}
+
+/// Returns the functions `def_id` calls, or `None` if its own body already
+/// makes it impure.
+fn direct_callees<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> Option<Vec<DefId>> {
+    if !tcx.is_mir_available(def_id) {
+        debug!("direct_callees: no MIR for {:?}", def_id);
+        return None;
+    }
+    let mir = tcx.optimized_mir(def_id);
+    let mut visitor = EffectVisitor {
+        tcx,
+        mir,
+        param_env: tcx.param_env(def_id).with_reveal_all(),
+        callees: vec![],
+        impure: false,
+    };
+    visitor.visit_mir(mir);
+    if visitor.impure {
+        None
+    } else {
+        Some(visitor.callees)
+    }
+}
+
+struct EffectVisitor<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    callees: Vec<DefId>,
+    impure: bool,
+}
+
+impl<'a, 'tcx> EffectVisitor<'a, 'tcx> {
+    fn call(&mut self, func: &Operand<'tcx>) {
+        let (def_id, substs) = match func.ty(self.mir, self.tcx).sty {
+            ty::FnDef(def_id, substs) => (def_id, substs),
+            _ => {
+                self.impure = true;
+                return;
+            }
+        };
+        match Instance::resolve(self.tcx, self.param_env, def_id, substs) {
+            Some(Instance { def: InstanceDef::Item(def_id), .. }) => {
+                self.callees.push(def_id);
+            }
+            Some(Instance { def: InstanceDef::Intrinsic(def_id), .. })
+                if is_pure_intrinsic(&self.tcx.item_name(def_id).as_str()) => {}
+            _ => self.impure = true,
+        }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for EffectVisitor<'a, 'tcx> {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        if context.is_mutating_use() && !is_local_memory(place) {
+            self.impure = true;
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       location: Location) {
+        if let StatementKind::InlineAsm { .. } = statement.kind {
+            self.impure = true;
+        }
+        self.super_statement(block, statement, location);
+    }
+
+    fn visit_terminator_kind(&mut self,
+                             block: BasicBlock,
+                             kind: &TerminatorKind<'tcx>,
+                             location: Location) {
+        match *kind {
+            TerminatorKind::Call { ref func, .. } => self.call(func),
+            TerminatorKind::Drop { .. } |
+            TerminatorKind::DropAndReplace { .. } |
+            TerminatorKind::Yield { .. } |
+            TerminatorKind::GeneratorDrop => self.impure = true,
+            _ => {}
+        }
+        self.super_terminator_kind(block, kind, location);
+    }
+}
+
+/// Whether `place` is a local or a part of one, as opposed to memory that
+/// is reached through a pointer or a static.
+fn is_local_memory(place: &Place<'_>) -> bool {
+    match *place {
+        Place::Local(_) => true,
+        Place::Promoted(_) | Place::Static(_) => false,
+        Place::Projection(ref proj) => match proj.elem {
+            ProjectionElem::Deref => false,
+            _ => is_local_memory(&proj.base),
+        },
+    }
+}
+
+pub(crate) fn is_pure_intrinsic(name: &str) -> bool {
+    match name {
+        "add_with_overflow" | "sub_with_overflow" | "mul_with_overflow" |
+        "wrapping_add" | "wrapping_sub" | "wrapping_mul" |
+        "overflowing_add" | "overflowing_sub" | "overflowing_mul" |
+        "rotate_left" | "rotate_right" |
+        "ctpop" | "ctlz" | "cttz" | "bswap" | "bitreverse" |
+        "size_of" | "min_align_of" | "pref_align_of" | "needs_drop" |
+        "type_id" | "type_name" => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,67 @@
+//! A pass that replaces unsigned remainders by a power of two with a mask.
+//!
+//! For unsigned `x`, `x % 2^k == x & (2^k - 1)`. Signed remainders take the
+//! sign of the dividend (`-1 % 8 == -1`, but `-1 & 7 == 7`), so they are left
+//! alone, as are remainders by anything that is not a power of two.
+
+use rustc::mir::*;
+use rustc::ty::{self, TyCtxt, ParamEnv};
+use crate::transform::{MirPass, MirSource};
+
+pub struct RemainderByPowerOfTwo;
+
+impl MirPass for RemainderByPowerOfTwo {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when optimizing MIR (at any level).
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return
+        }
+
+        let (basic_blocks, local_decls) = mir.basic_blocks_and_local_decls_mut();
+        for data in basic_blocks {
+            for statement in &mut data.statements {
+                let (op, lhs, rhs) = match statement.kind {
+                    StatementKind::Assign(_, box Rvalue::BinaryOp(ref mut op,
+                                                                  ref lhs,
+                                                                  ref mut rhs))
+                        if *op == BinOp::Rem => (op, lhs, rhs),
+                    _ => continue,
+                };
+                let ty = lhs.ty(&*local_decls, tcx);
+                match ty.sty {
+                    ty::Uint(_) => {}
+                    _ => continue,
+                }
+                let (divisor, span) = match *rhs {
+                    Operand::Constant(ref c) if c.ty == ty => {
+                        match c.literal.map_evaluated(|c| {
+                            c.to_bits(tcx, ParamEnv::empty().and(ty))
+                        }) {
+                            Some(divisor) => (divisor, c.span),
+                            None => continue,
+                        }
+                    }
+                    _ => continue,
+                };
+                if !divisor.is_power_of_two() {
+                    continue;
+                }
+                debug!("replacing remainder by {:?} with a mask", rhs);
+                *op = BinOp::BitAnd;
+                *rhs = Operand::Constant(box Constant {
+                    span,
+                    ty,
+                    user_ty: None,
+                    literal: tcx.mk_lazy_const(ty::LazyConst::Evaluated(ty::Const::from_bits(
+                        tcx,
+                        divisor - 1,
+                        ParamEnv::empty().and(ty),
+                    ))),
+                });
+            }
+        }
+    }
+}
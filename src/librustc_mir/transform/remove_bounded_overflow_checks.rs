@@ -0,0 +1,413 @@
+//! A pass that removes overflow checks of increments that cannot overflow.
+//!
+//! In a counted loop such as
+//!
+//!     let mut i = 0u8;
+//!     while i < 100 {
+//!         ...
+//!         i += 1;
+//!     }
+//!
+//! the increment is only reached while `i < 100`, so `i + 1` is at most
+//! `100` and its overflow check can never fail. We find such checks with a
+//! forward dataflow analysis that tracks a known upper bound for unsigned
+//! integer locals:
+//!
+//! * assigning a constant gives the local that bound;
+//! * the `true` edge of a `switchInt` on `Lt(LOCAL, const N)` (or `Le`)
+//!   bounds `LOCAL` by `N - 1` (or `N`);
+//! * `CheckedAdd(LOCAL, const C)` cannot overflow if `LOCAL`'s bound plus
+//!   `C` fits in the type, and its result is bounded by that sum;
+//! * any other write forgets what is known about a local.
+//!
+//! Where control flow joins, the larger bound is kept. A block whose entry
+//! state keeps changing after `MAX_VISITS` visits forgets everything, which
+//! bounds the time taken by loops whose bound keeps growing. Locals that are
+//! ever borrowed are never tracked, since they could be changed behind our
+//! back. Overflow checks that are proven redundant are replaced by a `goto`.
+
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::{self, Ty, TyCtxt, ParamEnv};
+use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::indexed_vec::IndexVec;
+use syntax::ast::UintTy;
+use crate::transform::{MirPass, MirSource};
+
+/// How often a block is visited before its entry state is given up on.
+const MAX_VISITS: usize = 8;
+
+pub struct RemoveBoundedOverflowChecks;
+
+impl MirPass for RemoveBoundedOverflowChecks {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let redundant = find_redundant_checks(tcx, mir);
+        for bb in redundant {
+            let terminator = mir[bb].terminator_mut();
+            let target = match terminator.kind {
+                TerminatorKind::Assert { target, .. } => target,
+                _ => bug!("redundant overflow check is not an `Assert`"),
+            };
+            debug!("removing redundant overflow check in {:?}", bb);
+            terminator.kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// What is known about the locals at some point.
+#[derive(Clone, Default, PartialEq)]
+struct State {
+    /// Inclusive upper bounds of unsigned integer locals.
+    bounds: FxHashMap<Local, u128>,
+    /// Locals holding the result of a `CheckedAdd` that cannot overflow,
+    /// with the bound of the sum.
+    sums: FxHashMap<Local, u128>,
+}
+
+impl State {
+    fn forget(&mut self, local: Local) {
+        self.bounds.remove(&local);
+        self.sums.remove(&local);
+    }
+
+    /// Keeps what is known in both `self` and `other`, with the larger bound.
+    fn join(&mut self, other: &State) {
+        fn join_map(map: &mut FxHashMap<Local, u128>, other: &FxHashMap<Local, u128>) {
+            map.retain(|local, _| other.contains_key(local));
+            for (local, bound) in map.iter_mut() {
+                *bound = (*bound).max(other[local]);
+            }
+        }
+        join_map(&mut self.bounds, &other.bounds);
+        join_map(&mut self.sums, &other.sums);
+    }
+}
+
+/// What is known within a single block, on top of its `State`.
+#[derive(Default)]
+struct BlockFacts {
+    /// Locals holding a copy of another local.
+    copies: FxHashMap<Local, Local>,
+    /// Locals holding `LOCAL < N` (or `LOCAL <= N - 1`), as `(LOCAL, N - 1)`.
+    conds: FxHashMap<Local, (Local, u128)>,
+}
+
+impl BlockFacts {
+    fn forget(&mut self, local: Local) {
+        self.copies.remove(&local);
+        self.copies.retain(|_, &mut source| source != local);
+        self.conds.remove(&local);
+        self.conds.retain(|_, &mut (compared, _)| compared != local);
+    }
+}
+
+struct Analysis<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    borrowed: BitSet<Local>,
+    /// The maximum value of each tracked local's type.
+    max: IndexVec<Local, Option<u128>>,
+}
+
+impl<'a, 'tcx> Analysis<'a, 'tcx> {
+    fn constant(&self, operand: &Operand<'tcx>, ty: Ty<'tcx>) -> Option<u128> {
+        match *operand {
+            Operand::Constant(ref c) if c.ty == ty => {
+                c.literal.map_evaluated(|c| c.to_bits(self.tcx, ParamEnv::empty().and(ty)))
+            }
+            _ => None,
+        }
+    }
+
+    /// The tracked local `operand` is (a copy of).
+    fn local(&self, operand: &Operand<'tcx>, facts: &BlockFacts) -> Option<Local> {
+        match *operand {
+            Operand::Copy(Place::Local(local)) | Operand::Move(Place::Local(local)) => {
+                let local = facts.copies.get(&local).cloned().unwrap_or(local);
+                if self.max[local].is_some() { Some(local) } else { None }
+            }
+            _ => None,
+        }
+    }
+
+    fn bound(&self, operand: &Operand<'tcx>, state: &State) -> Option<u128> {
+        match *operand {
+            Operand::Copy(Place::Local(local)) | Operand::Move(Place::Local(local)) => {
+                state.bounds.get(&local).cloned()
+            }
+            _ => None,
+        }
+    }
+
+    fn statement(&self,
+                 statement: &Statement<'tcx>,
+                 location: Location,
+                 state: &mut State,
+                 facts: &mut BlockFacts) {
+        // Work out what the statement tells us before forgetting what it
+        // overwrites, as it may read the local it writes to.
+        let mut bound = None;
+        let mut sum = None;
+        let mut copy = None;
+        let mut cond = None;
+        let dest = match statement.kind {
+            StatementKind::Assign(Place::Local(dest), box ref rvalue)
+                if !self.borrowed.contains(dest) => {
+                let ty = self.mir.local_decls[dest].ty;
+                match *rvalue {
+                    Rvalue::Use(ref operand @ Operand::Constant(_)) => {
+                        bound = self.constant(operand, ty);
+                    }
+                    Rvalue::Use(Operand::Copy(Place::Projection(box ref proj))) |
+                    Rvalue::Use(Operand::Move(Place::Projection(box ref proj))) => {
+                        // The result of a `CheckedAdd` that cannot overflow.
+                        if let (&Place::Local(tuple), &ProjectionElem::Field(field, _)) =
+                            (&proj.base, &proj.elem) {
+                            if field.index() == 0 {
+                                bound = state.sums.get(&tuple).cloned();
+                            }
+                        }
+                    }
+                    Rvalue::Use(ref operand) => {
+                        bound = self.bound(operand, state);
+                        copy = self.local(operand, facts);
+                    }
+                    Rvalue::BinaryOp(op @ BinOp::Lt, ref lhs, ref rhs) |
+                    Rvalue::BinaryOp(op @ BinOp::Le, ref lhs, ref rhs) => {
+                        if let Some(local) = self.local(lhs, facts) {
+                            let ty = self.mir.local_decls[local].ty;
+                            match (op, self.constant(rhs, ty)) {
+                                (BinOp::Lt, Some(n)) if n > 0 => cond = Some((local, n - 1)),
+                                (BinOp::Le, Some(n)) => cond = Some((local, n)),
+                                _ => {}
+                            }
+                        }
+                    }
+                    Rvalue::CheckedBinaryOp(BinOp::Add, ref lhs, ref rhs) => {
+                        if let Some(local) = self.local(lhs, facts) {
+                            let ty = self.mir.local_decls[local].ty;
+                            let max = self.max[local].unwrap();
+                            let lhs_bound = state.bounds.get(&local).cloned()
+                                .or_else(|| self.bound(lhs, state));
+                            if let (Some(b), Some(c)) = (lhs_bound, self.constant(rhs, ty)) {
+                                if b <= max && c <= max - b {
+                                    sum = Some(b + c);
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                Some(dest)
+            }
+            _ => None,
+        };
+
+        let mut written = WrittenLocals { locals: vec![] };
+        written.visit_statement(location.block, statement, location);
+        for local in written.locals {
+            state.forget(local);
+            facts.forget(local);
+        }
+
+        if let Some(dest) = dest {
+            if self.max[dest].is_some() {
+                if let Some(bound) = bound {
+                    state.bounds.insert(dest, bound);
+                }
+                if let Some(copy) = copy {
+                    facts.copies.insert(dest, copy);
+                }
+            }
+            if let Some(sum) = sum {
+                state.sums.insert(dest, sum);
+            }
+            if let Some(cond) = cond {
+                facts.conds.insert(dest, cond);
+            }
+        }
+    }
+}
+
+/// Returns the blocks whose terminator is an overflow check that cannot
+/// fail.
+fn find_redundant_checks<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   mir: &Mir<'tcx>)
+                                   -> Vec<BasicBlock> {
+    let mut borrowed = BorrowedLocals(BitSet::new_empty(mir.local_decls.len()));
+    borrowed.visit_mir(mir);
+    let borrowed = borrowed.0;
+    let max = mir.local_decls.iter_enumerated().map(|(local, decl)| {
+        if borrowed.contains(local) {
+            return None;
+        }
+        let bits = match decl.ty.sty {
+            ty::Uint(UintTy::U8) => 8,
+            ty::Uint(UintTy::U16) => 16,
+            ty::Uint(UintTy::U32) => 32,
+            ty::Uint(UintTy::U64) => 64,
+            ty::Uint(UintTy::U128) => 128,
+            ty::Uint(UintTy::Usize) => tcx.data_layout.pointer_size.bits(),
+            _ => return None,
+        };
+        Some(!0u128 >> (128 - bits))
+    }).collect();
+    let analysis = Analysis { tcx, mir, borrowed, max };
+
+    let mut entry_states: IndexVec<BasicBlock, Option<State>> =
+        IndexVec::from_elem(None, mir.basic_blocks());
+    let mut visits: IndexVec<BasicBlock, usize> = IndexVec::from_elem(0, mir.basic_blocks());
+    entry_states[START_BLOCK] = Some(State::default());
+    let order: Vec<BasicBlock> = traversal::reverse_postorder(mir).map(|(bb, _)| bb).collect();
+
+    // Run to a fixpoint, then look at the checks with the final states.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &bb in &order {
+            let state = match entry_states[bb] {
+                Some(ref state) => state.clone(),
+                None => continue,
+            };
+            for (target, exit_state) in exit_states(&analysis, bb, state).0 {
+                let visits = &mut visits[target];
+                let entry_state = &mut entry_states[target];
+                let new_state = match *entry_state {
+                    Some(ref old) => {
+                        let mut new = old.clone();
+                        new.join(&exit_state);
+                        new
+                    }
+                    None => exit_state,
+                };
+                if entry_state.as_ref() != Some(&new_state) {
+                    *visits += 1;
+                    *entry_state = Some(if *visits > MAX_VISITS {
+                        State::default()
+                    } else {
+                        new_state
+                    });
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    let mut redundant = vec![];
+    for &bb in &order {
+        if let Some(ref state) = entry_states[bb] {
+            if exit_states(&analysis, bb, state.clone()).1 {
+                redundant.push(bb);
+            }
+        }
+    }
+    redundant
+}
+
+/// Runs `bb` on `state`, returning the state along each of its outgoing
+/// edges, and whether its terminator is an overflow check that cannot fail.
+fn exit_states<'a, 'tcx>(analysis: &Analysis<'a, 'tcx>,
+                         bb: BasicBlock,
+                         mut state: State)
+                         -> (Vec<(BasicBlock, State)>, bool) {
+    let data = &analysis.mir[bb];
+    let mut facts = BlockFacts::default();
+    for (statement_index, statement) in data.statements.iter().enumerate() {
+        let location = Location { block: bb, statement_index };
+        analysis.statement(statement, location, &mut state, &mut facts);
+    }
+
+    let terminator = data.terminator();
+    let redundant = match terminator.kind {
+        TerminatorKind::Assert {
+            cond: Operand::Move(Place::Projection(box ref proj)), expected: false, ..
+        } |
+        TerminatorKind::Assert {
+            cond: Operand::Copy(Place::Projection(box ref proj)), expected: false, ..
+        } => {
+            match (&proj.base, &proj.elem) {
+                (&Place::Local(tuple), &ProjectionElem::Field(field, _)) => {
+                    field.index() == 1 && state.sums.contains_key(&tuple)
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+
+    // The `true` edge of a comparison bounds the compared local.
+    let mut refined = None;
+    if let TerminatorKind::SwitchInt { discr: Operand::Move(Place::Local(cond)),
+                                       ref values, ref targets, .. } |
+           TerminatorKind::SwitchInt { discr: Operand::Copy(Place::Local(cond)),
+                                       ref values, ref targets, .. } = terminator.kind {
+        if let Some(&(local, bound)) = facts.conds.get(&cond) {
+            if values[..] == [0] && targets[0] != targets[1] {
+                refined = Some((targets[1], local, bound));
+            }
+        }
+    }
+
+    let location = Location { block: bb, statement_index: data.statements.len() };
+    let mut written = WrittenLocals { locals: vec![] };
+    written.visit_terminator(bb, terminator, location);
+    for local in written.locals {
+        state.forget(local);
+    }
+
+    let exits = terminator.successors().map(|&target| {
+        let mut exit_state = state.clone();
+        if let Some((true_target, local, bound)) = refined {
+            if target == true_target {
+                let old = exit_state.bounds.get(&local).cloned().unwrap_or(bound);
+                exit_state.bounds.insert(local, old.min(bound));
+            }
+        }
+        (target, exit_state)
+    }).collect();
+    (exits, redundant)
+}
+
+/// Collects the locals a statement or terminator writes to or ends the
+/// storage of.
+struct WrittenLocals {
+    locals: Vec<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for WrittenLocals {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        if context.is_mutating_use() || context.is_storage_marker() {
+            self.locals.push(local);
+        }
+    }
+}
+
+/// Collects the locals that are (partly) borrowed anywhere in the body.
+struct BorrowedLocals(BitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        if context.is_borrow() {
+            let mut base = place;
+            while let Place::Projection(ref proj) = *base {
+                base = &proj.base;
+            }
+            if let Place::Local(local) = *base {
+                self.0.insert(local);
+            }
+        }
+        self.super_place(place, context, location);
+    }
+}
@@ -0,0 +1,167 @@
+//! A pass that removes `Retag` statements that repeat an earlier retag of
+//! the same place.
+//!
+//! When the place still holds the pointer the earlier retag gave a fresh
+//! tag, and nothing that could have invalidated that tag happened since,
+//! retagging it again only replaces one valid tag with another. Such
+//! repeated retags are left behind by the inliner, which keeps the
+//! function-entry retags of the callee's arguments while the caller already
+//! retagged them when it computed them.
+//!
+//! The pass is deliberately conservative about what may come in between:
+//! only storage markers and assignments that touch neither the retagged
+//! local nor any memory a pointer could refer to, i.e. locals that are
+//! never borrowed, and no dereferences. A retag is only known to hold at
+//! the start of a block if the block's single predecessor ends in a `goto`,
+//! so any call, drop or branch in between keeps the later retag.
+
+use rustc::mir::*;
+use rustc::mir::traversal;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::indexed_vec::IndexVec;
+use crate::transform::{MirPass, MirSource};
+
+pub struct RemoveRedundantRetags;
+
+impl MirPass for RemoveRedundantRetags {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when optimizing MIR (at any level).
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return
+        }
+        if !tcx.sess.opts.debugging_opts.mir_emit_retag {
+            return;
+        }
+
+        for location in redundant_retags(mir) {
+            debug!("removing redundant retag at {:?}", location);
+            mir[location.block].statements[location.statement_index].make_nop();
+        }
+    }
+}
+
+/// Returns the locations of the retags that repeat a retag still in effect.
+fn redundant_retags<'tcx>(mir: &Mir<'tcx>) -> Vec<Location> {
+    let mut borrowed = BorrowedLocals(BitSet::new_empty(mir.local_decls.len()));
+    borrowed.visit_mir(mir);
+    let borrowed = borrowed.0;
+
+    let predecessors = mir.predecessors();
+    // The retags still in effect at the end of each block that ends in a
+    // `goto`.
+    let mut live_at_goto: IndexVec<BasicBlock, Option<Vec<(RetagKind, Place<'tcx>)>>> =
+        IndexVec::from_elem(None, mir.basic_blocks());
+    let mut redundant = vec![];
+    for (bb, data) in traversal::reverse_postorder(mir) {
+        let mut live = match predecessors[bb][..] {
+            [pred] => live_at_goto[pred].clone().unwrap_or_default(),
+            _ => vec![],
+        };
+        for (index, statement) in data.statements.iter().enumerate() {
+            match statement.kind {
+                StatementKind::Retag(kind, ref place) => {
+                    if live.iter().any(|&(k, ref p)| k == kind && p == place) {
+                        redundant.push(Location { block: bb, statement_index: index });
+                    } else {
+                        // Retagging reborrows from the pointer, which may
+                        // invalidate the tags of any other place.
+                        live.clear();
+                        live.push((kind, place.clone()));
+                    }
+                }
+                _ => live.retain(|&(_, ref place)| {
+                    preserves_retag(statement, place.base_local(), &borrowed)
+                }),
+            }
+        }
+        if let TerminatorKind::Goto { .. } = data.terminator().kind {
+            live_at_goto[bb] = Some(live);
+        }
+    }
+    redundant
+}
+
+/// Whether `statement` cannot invalidate the tag of the pointer held in
+/// `local`.
+fn preserves_retag<'tcx>(statement: &Statement<'tcx>,
+                         local: Option<Local>,
+                         borrowed: &BitSet<Local>)
+                         -> bool {
+    let local = match local {
+        Some(local) => local,
+        None => return false,
+    };
+    match statement.kind {
+        StatementKind::StorageLive(l) |
+        StatementKind::StorageDead(l) => l != local,
+        StatementKind::Nop => true,
+        StatementKind::Assign(..) => {
+            let mut visitor = OnlyUnborrowedLocals {
+                retagged: local,
+                borrowed,
+                ok: true,
+            };
+            visitor.visit_statement(START_BLOCK, statement, Location::START);
+            visitor.ok
+        }
+        _ => false,
+    }
+}
+
+/// Collects the locals whose address is taken anywhere in the body.
+struct BorrowedLocals(BitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Ref(_, _, ref place) = *rvalue {
+            if let Some(local) = place.base_local() {
+                self.0.insert(local);
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
+
+/// Checks that a statement does not borrow, does not dereference, and only
+/// mentions locals other than `retagged` that are never borrowed.
+struct OnlyUnborrowedLocals<'a> {
+    retagged: Local,
+    borrowed: &'a BitSet<Local>,
+    ok: bool,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for OnlyUnborrowedLocals<'a> {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Ref(..) = *rvalue {
+            self.ok = false;
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        match *place {
+            Place::Projection(box Projection { elem: ProjectionElem::Deref, .. }) |
+            Place::Promoted(_) |
+            Place::Static(_) => self.ok = false,
+            Place::Local(_) | Place::Projection(_) => {}
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self,
+                   local: &Local,
+                   _context: PlaceContext<'tcx>,
+                   _location: Location) {
+        if *local == self.retagged || self.borrowed.contains(*local) {
+            self.ok = false;
+        }
+    }
+}
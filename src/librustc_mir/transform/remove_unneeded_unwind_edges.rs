@@ -0,0 +1,165 @@
+//! A pass that removes the unwind edges of calls that cannot unwind.
+//!
+//! Every call made while something needs to be dropped gets a `cleanup`
+//! edge to a landing pad, even if the callee can never panic. This pass
+//! drops that edge when the callee provably does not unwind, which lets the
+//! final CFG simplification remove landing pads nothing jumps to anymore.
+//!
+//! A function cannot unwind if it is marked `#[rustc_allocator_nounwind]`,
+//! or if its optimized MIR
+//!
+//! * contains no assertions, drops, or generator suspensions;
+//! * only calls functions that cannot unwind and a handful of intrinsics
+//!   that never panic.
+//!
+//! As in the purity analysis, calls through function pointers and trait
+//! objects, and calls to functions without MIR (such as foreign functions)
+//! are assumed to unwind. Drops are always assumed to unwind, since the
+//! drop glue may run arbitrary `Drop` impls.
+
+use rustc::hir::CodegenFnAttrFlags;
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use crate::transform::{MirPass, MirSource};
+use crate::transform::purity::{holds_for_reachable, is_pure_intrinsic};
+
+pub struct RemoveUnneededUnwindEdges;
+
+impl MirPass for RemoveUnneededUnwindEdges {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+        if tcx.sess.no_landing_pads() || source.promoted.is_some() {
+            return;
+        }
+
+        let caller = source.def_id();
+        let param_env = tcx.param_env(caller).with_reveal_all();
+        let mut cannot_unwind_cache = FxHashMap::default();
+        let mut edges = vec![];
+        for (bb, data) in mir.basic_blocks().iter_enumerated() {
+            let func = match data.terminator().kind {
+                TerminatorKind::Call { ref func, cleanup: Some(_), .. } => func,
+                _ => continue,
+            };
+            let callee = match resolve_call(tcx, param_env, mir, func) {
+                Some(Callee::Intrinsic(name)) => {
+                    if cannot_unwind_intrinsic(&name) {
+                        edges.push(bb);
+                    }
+                    continue;
+                }
+                Some(Callee::Item(callee)) => callee,
+                None => continue,
+            };
+            let cannot_unwind = *cannot_unwind_cache.entry(callee).or_insert_with(|| {
+                holds_for_reachable(callee, |def_id| direct_callees(tcx, caller, def_id))
+            });
+            debug!("cannot_unwind({:?}) = {}", callee, cannot_unwind);
+            if cannot_unwind {
+                edges.push(bb);
+            }
+        }
+
+        for bb in edges {
+            debug!("removing the unwind edge of {:?}", bb);
+            if let TerminatorKind::Call { ref mut cleanup, .. } = mir[bb].terminator_mut().kind {
+                *cleanup = None;
+            }
+        }
+    }
+}
+
+enum Callee {
+    Item(DefId),
+    Intrinsic(String),
+}
+
+/// Resolves the function a call refers to, or returns `None` for a call
+/// through a pointer or a call that cannot be resolved to a single
+/// function.
+fn resolve_call<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          param_env: ty::ParamEnv<'tcx>,
+                          mir: &Mir<'tcx>,
+                          func: &Operand<'tcx>)
+                          -> Option<Callee> {
+    let (def_id, substs) = match func.ty(mir, tcx).sty {
+        ty::FnDef(def_id, substs) => (def_id, substs),
+        _ => return None,
+    };
+    match Instance::resolve(tcx, param_env, def_id, substs)?.def {
+        InstanceDef::Item(def_id) => Some(Callee::Item(def_id)),
+        InstanceDef::Intrinsic(def_id) => {
+            Some(Callee::Intrinsic(tcx.item_name(def_id).to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the functions `def_id` calls, or `None` if it may unwind by
+/// itself. This is called while optimizing `caller`.
+fn direct_callees<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                            caller: DefId,
+                            def_id: DefId)
+                            -> Option<Vec<DefId>> {
+    let flags = tcx.codegen_fn_attrs(def_id).flags;
+    if flags.contains(CodegenFnAttrFlags::RUSTC_ALLOCATOR_NOUNWIND) {
+        return Some(vec![]);
+    }
+    if !tcx.is_mir_available(def_id) {
+        debug!("direct_callees: no MIR for {:?}", def_id);
+        return None;
+    }
+
+    // As in the inliner, only ask for the optimized MIR of local functions
+    // with a higher node id than the caller, so that two functions can never
+    // wait for each other's MIR. This does not work with incremental
+    // compilation.
+    let caller_node_id = tcx.hir().as_local_node_id(caller).unwrap();
+    if let Some(node_id) = tcx.hir().as_local_node_id(def_id) {
+        if tcx.dep_graph.is_fully_enabled() || node_id.as_u32() <= caller_node_id.as_u32() {
+            return None;
+        }
+    }
+
+    let mir = tcx.optimized_mir(def_id);
+    let param_env = tcx.param_env(def_id).with_reveal_all();
+    let mut callees = vec![];
+    for data in mir.basic_blocks() {
+        match data.terminator().kind {
+            TerminatorKind::Call { ref func, .. } => {
+                match resolve_call(tcx, param_env, mir, func) {
+                    Some(Callee::Item(callee)) => callees.push(callee),
+                    Some(Callee::Intrinsic(ref name)) if cannot_unwind_intrinsic(name) => {}
+                    _ => return None,
+                }
+            }
+            TerminatorKind::Assert { .. } |
+            TerminatorKind::Drop { .. } |
+            TerminatorKind::DropAndReplace { .. } |
+            TerminatorKind::Yield { .. } |
+            TerminatorKind::GeneratorDrop => return None,
+            _ => {}
+        }
+    }
+    Some(callees)
+}
+
+fn cannot_unwind_intrinsic(name: &str) -> bool {
+    if is_pure_intrinsic(name) {
+        return true;
+    }
+    match name {
+        "abort" | "unreachable" | "assume" | "likely" | "unlikely" |
+        "transmute" | "forget" | "discriminant_value" |
+        "copy" | "copy_nonoverlapping" | "write_bytes" => true,
+        _ => false,
+    }
+}
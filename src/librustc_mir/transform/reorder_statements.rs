@@ -0,0 +1,220 @@
+//! A pass that reorders the statements of a block to group the operations on
+//! the same local together.
+//!
+//! Building MIR interleaves independent computations the way the source
+//! interleaves them, e.g. `x ^= 1; y ^= 2; x &= 3; y &= 4;`. Moving each
+//! operation next to the previous one on the same local shortens the live
+//! ranges codegen has to keep in registers at the same time.
+//!
+//! Only statements that are provably independent are moved past each other.
+//! A block is split at every statement that is not a storage marker or an
+//! assignment that only reads and writes locals; in particular dereferences,
+//! statics, borrows and allocations are never moved, and nothing moves across
+//! them. Within the pieces in between, a statement stays after every earlier
+//! statement that writes a local it reads or writes, or reads a local it
+//! writes; moving a local out counts as writing it.
+//!
+//! The pass is only run with `-Z mir-reorder-statements`.
+
+use rustc::mir::*;
+use rustc::mir::visit::{NonMutatingUseContext, PlaceContext, Visitor};
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bit_set::BitSet;
+use std::mem;
+use crate::transform::{MirPass, MirSource};
+
+/// The number of statements reordered together. Longer runs of movable
+/// statements are split, which bounds the quadratic dependency checks.
+const MAX_SEGMENT_LEN: usize = 64;
+
+pub struct ReorderStatements;
+
+impl MirPass for ReorderStatements {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        if !tcx.sess.opts.debugging_opts.mir_reorder_statements {
+            return;
+        }
+
+        for (bb, data) in mir.basic_blocks_mut().iter_enumerated_mut() {
+            let order = block_order(&data.statements);
+            if order.iter().enumerate().all(|(index, &old)| index == old) {
+                continue;
+            }
+            debug!("reordering the statements of {:?} as {:?}", bb, order);
+            let mut old: Vec<_> = mem::replace(&mut data.statements, vec![])
+                .into_iter()
+                .map(Some)
+                .collect();
+            data.statements = order.into_iter().map(|index| old[index].take().unwrap()).collect();
+        }
+    }
+}
+
+/// The locals a statement reads and writes.
+struct Accesses {
+    reads: Vec<Local>,
+    writes: Vec<Local>,
+    storage_marker: bool,
+}
+
+impl Accesses {
+    /// Returns `None` if the statement must not be moved.
+    fn of(statement: &Statement<'_>) -> Option<Accesses> {
+        match statement.kind {
+            StatementKind::StorageLive(local) |
+            StatementKind::StorageDead(local) => Some(Accesses {
+                reads: vec![],
+                writes: vec![local],
+                storage_marker: true,
+            }),
+            StatementKind::Nop => Some(Accesses {
+                reads: vec![],
+                writes: vec![],
+                storage_marker: false,
+            }),
+            StatementKind::Assign(..) => {
+                let mut visitor = CollectAccesses {
+                    accesses: Accesses {
+                        reads: vec![],
+                        writes: vec![],
+                        storage_marker: false,
+                    },
+                    movable: true,
+                };
+                visitor.visit_statement(START_BLOCK, statement, Location::START);
+                if visitor.movable {
+                    Some(visitor.accesses)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `later` has to stay after `self`.
+    fn must_precede(&self, later: &Accesses) -> bool {
+        self.writes.iter().any(|l| later.reads.contains(l) || later.writes.contains(l)) ||
+            later.writes.iter().any(|l| self.reads.contains(l))
+    }
+
+    /// Whether both statements operate on a common local.
+    fn shares_local(&self, other: &Accesses) -> bool {
+        if self.storage_marker || other.storage_marker {
+            return false;
+        }
+        self.reads.iter().chain(&self.writes).any(|l| {
+            other.reads.contains(l) || other.writes.contains(l)
+        })
+    }
+}
+
+/// Returns the indices of `statements` in the order they should be in.
+fn block_order(statements: &[Statement<'_>]) -> Vec<usize> {
+    let mut order = Vec::with_capacity(statements.len());
+    let mut segment = vec![];
+    for (index, statement) in statements.iter().enumerate() {
+        match Accesses::of(statement) {
+            Some(accesses) => {
+                segment.push(accesses);
+                if segment.len() == MAX_SEGMENT_LEN {
+                    let start = index + 1 - segment.len();
+                    order.extend(segment_order(&segment).into_iter().map(|i| start + i));
+                    segment.clear();
+                }
+            }
+            None => {
+                let start = index - segment.len();
+                order.extend(segment_order(&segment).into_iter().map(|i| start + i));
+                segment.clear();
+                order.push(index);
+            }
+        }
+    }
+    let start = statements.len() - segment.len();
+    order.extend(segment_order(&segment).into_iter().map(|i| start + i));
+    order
+}
+
+/// Orders a run of movable statements: after each statement comes the first
+/// remaining one that operates on a local it operates on, preceded by the
+/// remaining statements that have to precede it. Without such a statement,
+/// the first remaining one comes next.
+fn segment_order(segment: &[Accesses]) -> Vec<usize> {
+    let mut scheduled = BitSet::new_empty(segment.len());
+    let mut order = Vec::with_capacity(segment.len());
+    let mut last = None;
+    while order.len() < segment.len() {
+        let related = last.and_then(|last: usize| {
+            (0..segment.len()).find(|&index| {
+                !scheduled.contains(index) && segment[last].shares_local(&segment[index])
+            })
+        });
+        let next = related.unwrap_or_else(|| {
+            (0..segment.len()).find(|&index| !scheduled.contains(index)).unwrap()
+        });
+        schedule(segment, next, &mut scheduled, &mut order);
+        last = Some(next);
+    }
+    order
+}
+
+/// Appends `index` to `order` after the statements that have to precede it.
+fn schedule(segment: &[Accesses],
+            index: usize,
+            scheduled: &mut BitSet<usize>,
+            order: &mut Vec<usize>) {
+    for earlier in 0..index {
+        if !scheduled.contains(earlier) && segment[earlier].must_precede(&segment[index]) {
+            schedule(segment, earlier, scheduled, order);
+        }
+    }
+    scheduled.insert(index);
+    order.push(index);
+}
+
+/// Collects the locals an assignment reads and writes, and whether it
+/// touches anything but locals.
+struct CollectAccesses {
+    accesses: Accesses,
+    movable: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for CollectAccesses {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        match *rvalue {
+            Rvalue::Ref(..) |
+            Rvalue::NullaryOp(NullOp::Box, _) => self.movable = false,
+            _ => {}
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        match *place {
+            Place::Projection(box Projection { elem: ProjectionElem::Deref, .. }) |
+            Place::Static(_) => self.movable = false,
+            Place::Local(_) | Place::Promoted(_) | Place::Projection(_) => {}
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self,
+                   &local: &Local,
+                   context: PlaceContext<'tcx>,
+                   _location: Location) {
+        match context {
+            PlaceContext::MutatingUse(_) |
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::Move) => {
+                self.accesses.writes.push(local)
+            }
+            _ => self.accesses.reads.push(local),
+        }
+    }
+}
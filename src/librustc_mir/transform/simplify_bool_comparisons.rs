@@ -0,0 +1,67 @@
+//! A pass that simplifies comparisons of booleans with constants.
+//!
+//! `x == true` and `x != false` are just `x`, and `x == false` and
+//! `x != true` are just `!x`. Such comparisons are common in code that
+//! spells out its conditions, and are replaced by a copy (or move) of the
+//! boolean or by its negation. Comparisons of anything but `bool`s are left
+//! alone.
+
+use rustc::ty::TyCtxt;
+use rustc::mir::*;
+use crate::transform::{MirPass, MirSource};
+
+pub struct SimplifyBoolComparisons;
+
+impl MirPass for SimplifyBoolComparisons {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _src: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when optimizing MIR (at any level).
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return
+        }
+
+        let (basic_blocks, local_decls) = mir.basic_blocks_and_local_decls_mut();
+        for data in basic_blocks {
+            for statement in &mut data.statements {
+                let rvalue = match statement.kind {
+                    StatementKind::Assign(_, box ref mut rvalue) => rvalue,
+                    _ => continue,
+                };
+                let simplified = match *rvalue {
+                    Rvalue::BinaryOp(op @ BinOp::Eq, ref lhs, ref rhs) |
+                    Rvalue::BinaryOp(op @ BinOp::Ne, ref lhs, ref rhs) => {
+                        if lhs.ty(&*local_decls, tcx) != tcx.types.bool {
+                            continue;
+                        }
+                        let (operand, value) = match (bool_constant(tcx, lhs),
+                                                      bool_constant(tcx, rhs)) {
+                            (_, Some(value)) => (lhs.clone(), value),
+                            (Some(value), None) => (rhs.clone(), value),
+                            (None, None) => continue,
+                        };
+                        // `x == true` and `x != false` are `x`.
+                        if value == (op == BinOp::Eq) {
+                            Rvalue::Use(operand)
+                        } else {
+                            Rvalue::UnaryOp(UnOp::Not, operand)
+                        }
+                    }
+                    _ => continue,
+                };
+                debug!("simplifying {:?} to {:?}", rvalue, simplified);
+                *rvalue = simplified;
+            }
+        }
+    }
+}
+
+fn bool_constant<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, operand: &Operand<'tcx>) -> Option<bool> {
+    match *operand {
+        Operand::Constant(ref c) if c.ty == tcx.types.bool => {
+            c.literal.map_evaluated(|c| c.assert_bool(tcx))
+        }
+        _ => None,
+    }
+}
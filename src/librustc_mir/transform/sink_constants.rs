@@ -0,0 +1,122 @@
+//! A pass that moves assignments of constants into the successor block that
+//! uses them.
+//!
+//! `let x = 42; if c { f(x) } else { 0 }` assigns `x` before the branch,
+//! although only one arm reads it. When all the reads of a local assigned
+//! once from a constant are in a single successor of the assigning block,
+//! the assignment is moved to the start of that successor, so the other
+//! paths do not do it.
+//!
+//! The successor must have the assigning block as its only predecessor. It
+//! then runs at most as often as the assigning block, so an assignment is
+//! never moved into a loop it was outside of, and it is never duplicated.
+
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashMap;
+use crate::transform::{MirPass, MirSource};
+use crate::util::def_use::DefUseAnalysis;
+
+pub struct SinkConstants;
+
+impl MirPass for SinkConstants {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when optimizing MIR (at any level).
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return
+        }
+
+        let mut sinks = constant_sinks(mir);
+        if sinks.is_empty() {
+            return;
+        }
+        // Remove the assignments back to front, so the indices of the ones
+        // still to be removed stay valid, and add them to their new blocks in
+        // their original order.
+        sinks.sort_by_key(|&(location, _)| location);
+        let mut sunk: FxHashMap<BasicBlock, Vec<Statement<'tcx>>> = FxHashMap::default();
+        for &(location, target) in sinks.iter().rev() {
+            debug!("moving the constant assignment at {:?} to {:?}", location, target);
+            let statement = mir[location.block].statements.remove(location.statement_index);
+            sunk.entry(target).or_default().insert(0, statement);
+        }
+        for (target, statements) in sunk {
+            mir[target].statements.splice(0..0, statements);
+        }
+    }
+}
+
+/// Returns the assignments of constants that can be moved, and the block
+/// each of them is moved to.
+fn constant_sinks<'tcx>(mir: &Mir<'tcx>) -> Vec<(Location, BasicBlock)> {
+    let mut def_use_analysis = DefUseAnalysis::new(mir);
+    def_use_analysis.analyze(mir);
+    let predecessors = mir.predecessors();
+
+    let mut sinks = vec![];
+    for local in mir.local_decls.indices() {
+        match mir.local_kind(local) {
+            LocalKind::Var | LocalKind::Temp => {}
+            LocalKind::Arg | LocalKind::ReturnPointer => continue,
+        }
+        let info = def_use_analysis.local_info(local);
+        let mut defs = info.defs_not_including_drop();
+        let def = match (defs.next(), defs.next()) {
+            (Some(def), None) => def.location,
+            _ => continue,
+        };
+        match mir[def.block].statements.get(def.statement_index) {
+            Some(&Statement {
+                kind: StatementKind::Assign(
+                    Place::Local(l),
+                    box Rvalue::Use(Operand::Constant(_)),
+                ),
+                ..
+            }) if l == local => {}
+            _ => continue,
+        }
+
+        // All the reads must be in the same block, other than the defining
+        // one.
+        let mut target = None;
+        let mut sinkable = true;
+        for place_use in &info.defs_and_uses {
+            if place_use.context.is_storage_marker() {
+                // The local must stay live from the assignment to the end
+                // of its block, and must not be made live again in the
+                // target.
+                let location = place_use.location;
+                if location.block == def.block {
+                    if location.statement_index > def.statement_index {
+                        sinkable = false;
+                    }
+                } else if place_use.context.is_storage_live_marker() {
+                    sinkable = false;
+                }
+                continue;
+            }
+            if place_use.location == def {
+                continue;
+            }
+            let block = place_use.location.block;
+            if block == def.block || target.map_or(false, |target| target != block) {
+                sinkable = false;
+            }
+            target = Some(block);
+        }
+        let target = match target {
+            Some(target) if sinkable => target,
+            _ => continue,
+        };
+
+        if mir[target].is_cleanup || predecessors[target][..] != [def.block] ||
+           !mir[def.block].terminator().successors().any(|&succ| succ == target) {
+            continue;
+        }
+        sinks.push((def, target));
+    }
+    sinks
+}
@@ -0,0 +1,183 @@
+//! A pass that turns self-recursive calls in tail position into jumps back to
+//! the start of the function.
+//!
+//! A call is in tail position if the function returns its result right away:
+//! the blocks after the call only end the storage of locals, copy the result
+//! into the return place if the call did not write it there directly, and
+//! return. Such a call to the function itself, with the same generic
+//! arguments, is replaced by assignments of the call's arguments to the
+//! function's arguments and a `goto` to the start block, so the recursion
+//! runs in a single stack frame.
+//!
+//! The frame is reused for the next "call", so the pass gives up on functions
+//! that borrow any of their locals, as such a borrow could be passed on to
+//! the recursive call. Calls with an unwind cleanup are kept too, since the
+//! caller still has something to drop if the callee panics.
+//!
+//! The pass is only run with `-Z mir-tail-call-opt`.
+
+use rustc::mir::*;
+use rustc::mir::visit::Visitor;
+use rustc::ty::{self, TyCtxt};
+use rustc::ty::subst::Substs;
+use rustc_target::spec::abi::Abi;
+use crate::transform::{MirPass, MirSource};
+
+pub struct TailCallToLoop;
+
+impl MirPass for TailCallToLoop {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        if !tcx.sess.opts.debugging_opts.mir_tail_call_opt {
+            return;
+        }
+        let def_id = source.def_id();
+        let id = tcx.hir().as_local_node_id(def_id).unwrap();
+        if !tcx.hir().body_owner_kind(id).is_fn_or_closure() || source.promoted.is_some() {
+            return;
+        }
+        // Closures cannot name themselves, and generators and `extern "rust-call"`
+        // functions do not receive their arguments as written in the call.
+        if tcx.is_closure(def_id) || mir.yield_ty.is_some() || mir.spread_arg.is_some() ||
+           tcx.fn_sig(def_id).abi() != Abi::Rust {
+            return;
+        }
+        let param_env = tcx.param_env(def_id);
+        if mir.args_iter().any(|arg| {
+            !mir.local_decls[arg].ty.is_sized(tcx.at(mir.span), param_env)
+        }) {
+            return;
+        }
+        let mut borrows = BorrowsFrameLocal(false);
+        borrows.visit_mir(mir);
+        if borrows.0 {
+            return;
+        }
+
+        let identity = tcx.erase_regions(&Substs::identity_for_item(tcx, def_id));
+        let mut tail_calls = vec![];
+        for (bb, data) in mir.basic_blocks().iter_enumerated() {
+            let (func, dest, target) = match data.terminator().kind {
+                TerminatorKind::Call {
+                    ref func,
+                    destination: Some((ref dest, target)),
+                    cleanup: None,
+                    ..
+                } => (func, dest, target),
+                _ => continue,
+            };
+            match func.ty(mir, tcx).sty {
+                ty::FnDef(callee, substs) if callee == def_id && substs == identity => {}
+                _ => continue,
+            }
+            if let Some(storage_dead) = tail_position(mir, dest, target) {
+                tail_calls.push((bb, storage_dead));
+            }
+        }
+
+        for (bb, storage_dead) in tail_calls {
+            debug!("replacing the tail call in {:?} by a jump to the start", bb);
+            let terminator = mir[bb].terminator.take().unwrap();
+            let source_info = terminator.source_info;
+            let args = match terminator.kind {
+                TerminatorKind::Call { args, .. } => args,
+                _ => bug!("replaced terminator is not a call"),
+            };
+            // The arguments may read the current values of the function's
+            // arguments, so they are all evaluated before any is assigned.
+            let mut statements = vec![];
+            let mut temps = vec![];
+            for arg in args {
+                let ty = arg.ty(&*mir, tcx);
+                let temp = mir.local_decls.push(LocalDecl::new_temp(ty, source_info.span));
+                statements.push(Statement {
+                    source_info,
+                    kind: StatementKind::Assign(Place::Local(temp), box Rvalue::Use(arg)),
+                });
+                temps.push(temp);
+            }
+            statements.extend(storage_dead);
+            for (arg, temp) in mir.args_iter().zip(temps) {
+                statements.push(Statement {
+                    source_info,
+                    kind: StatementKind::Assign(
+                        Place::Local(arg),
+                        box Rvalue::Use(Operand::Move(Place::Local(temp))),
+                    ),
+                });
+            }
+            let data = &mut mir[bb];
+            data.statements.extend(statements);
+            data.terminator = Some(Terminator {
+                source_info,
+                kind: TerminatorKind::Goto { target: START_BLOCK },
+            });
+        }
+    }
+}
+
+/// If a call writing to `dest` and continuing at `target` is in tail
+/// position, returns the `StorageDead` statements that follow it.
+fn tail_position<'tcx>(mir: &Mir<'tcx>,
+                       dest: &Place<'tcx>,
+                       target: BasicBlock)
+                       -> Option<Vec<Statement<'tcx>>> {
+    match *dest {
+        Place::Local(_) => {}
+        _ => return None,
+    }
+    let mut returned = *dest == Place::Local(RETURN_PLACE);
+    let mut storage_dead = vec![];
+    let mut bb = target;
+    // Bounds the walk through `goto`s, which may loop.
+    for _ in mir.basic_blocks().indices() {
+        let data = &mir[bb];
+        for statement in &data.statements {
+            match statement.kind {
+                StatementKind::StorageDead(_) => storage_dead.push(statement.clone()),
+                StatementKind::Nop => {}
+                StatementKind::Assign(Place::Local(RETURN_PLACE), box Rvalue::Use(ref operand))
+                        if !returned => {
+                    match *operand {
+                        Operand::Copy(ref place) |
+                        Operand::Move(ref place) if place == dest => returned = true,
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            }
+        }
+        match data.terminator().kind {
+            TerminatorKind::Goto { target } => bb = target,
+            TerminatorKind::Return if returned => return Some(storage_dead),
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Finds whether any local of the frame has its address taken.
+struct BorrowsFrameLocal(bool);
+
+impl<'tcx> Visitor<'tcx> for BorrowsFrameLocal {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Ref(_, _, ref place) = *rvalue {
+            if in_frame(place) {
+                self.0 = true;
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
+
+/// Whether `place` is (part of) a local rather than behind a pointer.
+fn in_frame(place: &Place<'_>) -> bool {
+    match *place {
+        Place::Local(_) => true,
+        Place::Projection(box Projection { elem: ProjectionElem::Deref, .. }) => false,
+        Place::Projection(ref proj) => in_frame(&proj.base),
+        Place::Promoted(_) | Place::Static(_) => false,
+    }
+}
@@ -12,11 +12,13 @@ mod graphviz;
 pub(crate) mod pretty;
 pub mod liveness;
 pub mod collect_writes;
+pub mod validate;
 
 pub use self::alignment::is_disaligned;
 pub use self::pretty::{dump_enabled, dump_mir, write_mir_pretty, PassWhere};
 pub use self::graphviz::{write_mir_graphviz};
 pub use self::graphviz::write_node_label as write_graphviz_node_label;
+pub use self::validate::{validate_body, ValidationError, ValidationErrorKind};
 
 /// If possible, suggest replacing `ref` with `ref mut`.
 pub fn suggest_ref_mut<'cx, 'gcx, 'tcx>(
@@ -0,0 +1,145 @@
+//! A sanity checker for the control-flow graph of a MIR body.
+//!
+//! Optimization passes rewrite terminators in place, and a mistake there
+//! (a dangling block index, a `SwitchInt` whose arrays got out of sync, ...)
+//! usually only surfaces much later as an ICE in codegen. Running this after
+//! every pass with `-Z validate-mir` catches such bugs at the pass that
+//! introduced them.
+
+use rustc::mir::*;
+use rustc_data_structures::indexed_vec::IndexVec;
+
+use std::fmt;
+
+/// An invariant violated by the terminator of `block`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    pub block: BasicBlock,
+    pub kind: ValidationErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// A successor refers to a block that does not exist.
+    SuccessorOutOfRange { target: BasicBlock, num_blocks: usize },
+    /// A `SwitchInt` does not have exactly one more target than values.
+    SwitchIntArityMismatch { values: usize, targets: usize },
+    /// An unwind edge leads to a block that is not a cleanup block.
+    UnwindToNonCleanup { target: BasicBlock },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ValidationErrorKind::SuccessorOutOfRange { target, num_blocks } => {
+                write!(f, "{:?}: successor {:?} is out of range (body has {} blocks)",
+                       self.block, target, num_blocks)
+            }
+            ValidationErrorKind::SwitchIntArityMismatch { values, targets } => {
+                write!(f, "{:?}: `SwitchInt` has {} values but {} targets (expected {})",
+                       self.block, values, targets, values + 1)
+            }
+            ValidationErrorKind::UnwindToNonCleanup { target } => {
+                write!(f, "{:?}: unwind edge to {:?}, which is not a cleanup block",
+                       self.block, target)
+            }
+        }
+    }
+}
+
+/// Checks that every terminator in `mir` only refers to existing blocks,
+/// that `SwitchInt` terminators have one more target than values, and
+/// that unwind edges lead into cleanup blocks.
+pub fn validate_body<'tcx>(mir: &Mir<'tcx>) -> Result<(), Vec<ValidationError>> {
+    validate_blocks(mir.basic_blocks())
+}
+
+/// The checks of `validate_body`, which only need the blocks of the body.
+fn validate_blocks<'tcx>(basic_blocks: &IndexVec<BasicBlock, BasicBlockData<'tcx>>)
+                         -> Result<(), Vec<ValidationError>> {
+    let num_blocks = basic_blocks.len();
+    let mut errors = vec![];
+
+    for (block, data) in basic_blocks.iter_enumerated() {
+        // Blocks are temporarily left without a terminator only while
+        // they are being built, never between passes.
+        let terminator = match data.terminator {
+            Some(ref terminator) => terminator,
+            None => continue,
+        };
+
+        for &target in terminator.successors() {
+            if target.index() >= num_blocks {
+                errors.push(ValidationError {
+                    block,
+                    kind: ValidationErrorKind::SuccessorOutOfRange { target, num_blocks },
+                });
+            }
+        }
+
+        if let TerminatorKind::SwitchInt { ref values, ref targets, .. } = terminator.kind {
+            if targets.len() != values.len() + 1 {
+                errors.push(ValidationError {
+                    block,
+                    kind: ValidationErrorKind::SwitchIntArityMismatch {
+                        values: values.len(),
+                        targets: targets.len(),
+                    },
+                });
+            }
+        }
+
+        if let Some(&Some(target)) = terminator.unwind() {
+            // Out-of-range targets were already reported above.
+            if target.index() < num_blocks && !basic_blocks[target].is_cleanup {
+                errors.push(ValidationError {
+                    block,
+                    kind: ValidationErrorKind::UnwindToNonCleanup { target },
+                });
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc::mir::*;
+    use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+    use syntax_pos::DUMMY_SP;
+    use super::{validate_blocks, ValidationError, ValidationErrorKind};
+
+    fn block<'tcx>(kind: TerminatorKind<'tcx>) -> BasicBlockData<'tcx> {
+        BasicBlockData::new(Some(Terminator {
+            source_info: SourceInfo { span: DUMMY_SP, scope: OUTERMOST_SOURCE_SCOPE },
+            kind,
+        }))
+    }
+
+    #[test]
+    fn goto_out_of_range() {
+        let mut blocks = IndexVec::new();
+        blocks.push(block(TerminatorKind::Goto { target: BasicBlock::new(1) }));
+        blocks.push(block(TerminatorKind::Goto { target: BasicBlock::new(5) }));
+        assert_eq!(validate_blocks(&blocks), Err(vec![ValidationError {
+            block: BasicBlock::new(1),
+            kind: ValidationErrorKind::SuccessorOutOfRange {
+                target: BasicBlock::new(5),
+                num_blocks: 2,
+            },
+        }]));
+    }
+
+    #[test]
+    fn goto_in_range() {
+        let mut blocks = IndexVec::new();
+        blocks.push(block(TerminatorKind::Goto { target: BasicBlock::new(1) }));
+        blocks.push(block(TerminatorKind::Return));
+        assert_eq!(validate_blocks(&blocks), Ok(()));
+    }
+}
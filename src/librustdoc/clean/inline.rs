@@ -8,7 +8,7 @@ use syntax_pos::Span;
 
 use rustc::hir;
 use rustc::hir::def::{Def, CtorKind};
-use rustc::hir::def_id::DefId;
+use rustc::hir::def_id::{CrateNum, DefId, CRATE_DEF_INDEX};
 use rustc_metadata::cstore::LoadedMacro;
 use rustc::ty;
 use rustc::util::nodemap::FxHashSet;
@@ -199,6 +199,7 @@ pub fn build_external_trait(cx: &DocContext<'_, '_, '_>, did: DefId) -> clean::T
         bounds: supertrait_bounds,
         is_spotlight,
         is_auto,
+        object_safety_violations: clean::object_safety_violations(cx, did),
     }
 }
 
@@ -231,6 +232,7 @@ fn build_enum(cx: &DocContext<'_, '_, '_>, did: DefId) -> clean::Enum {
         generics: (cx.tcx.generics_of(did), &predicates).clean(cx),
         variants_stripped: false,
         variants: cx.tcx.adt_def(did).variants.clean(cx),
+        variances: clean::type_param_variances(cx, did),
     }
 }
 
@@ -247,6 +249,7 @@ fn build_struct(cx: &DocContext<'_, '_, '_>, did: DefId) -> clean::Struct {
         generics: (cx.tcx.generics_of(did), &predicates).clean(cx),
         fields: variant.fields.clean(cx),
         fields_stripped: false,
+        variances: clean::type_param_variances(cx, did),
     }
 }
 
@@ -259,6 +262,7 @@ fn build_union(cx: &DocContext<'_, '_, '_>, did: DefId) -> clean::Union {
         generics: (cx.tcx.generics_of(did), &predicates).clean(cx),
         fields: variant.fields.clean(cx),
         fields_stripped: false,
+        variances: clean::type_param_variances(cx, did),
     }
 }
 
@@ -421,6 +425,36 @@ fn build_module(
     }
 }
 
+/// Builds the documentation of the dependency `cnum` from its metadata, as if it was the crate
+/// being documented. This is used to render dependencies with `--bundle-crate`.
+pub fn build_crate(cx: &DocContext<'_, '_, '_>, cnum: CrateNum) -> clean::Crate {
+    let clean::ExternalCrate { name, src, .. } = cnum.clean(cx);
+    let did = DefId { krate: cnum, index: CRATE_DEF_INDEX };
+    let mut module = build_module(cx, did, &mut FxHashSet::default());
+    module.is_crate = true;
+    clean::Crate {
+        name,
+        version: None,
+        src,
+        module: Some(clean::Item {
+            source: cx.tcx.def_span(did).clean(cx),
+            // Like the root module of the documented crate, this one is unnamed: the stack of
+            // the cache starts with the crate name, and `Context::krate` names it on rendering.
+            name: Some(String::new()),
+            attrs: load_attrs(cx, did),
+            inner: clean::ModuleItem(module),
+            visibility: Some(clean::Public),
+            stability: cx.tcx.lookup_stability(did).clean(cx),
+            deprecation: cx.tcx.lookup_deprecation(did).clean(cx),
+            def_id: did,
+        }),
+        externs: Vec::new(),
+        primitives: Vec::new(),
+        external_traits: cx.external_traits.clone(),
+        masked_crates: FxHashSet::default(),
+    }
+}
+
 pub fn print_inlined_const(cx: &DocContext<'_, '_, '_>, did: DefId) -> String {
     if let Some(node_id) = cx.tcx.hir().as_local_node_id(did) {
         cx.tcx.hir().node_to_pretty_string(node_id)
@@ -852,6 +852,25 @@ impl Attributes {
         false
     }
 
+    /// Returns the configuration required by the plain `#[cfg(...)]` attributes of the item, as
+    /// opposed to the `#[doc(cfg(...))]` ones collected in `cfg`.
+    pub fn cfg_attrs(&self) -> Option<Cfg> {
+        let mut cfg = Cfg::True;
+        for attr in &self.other_attrs {
+            if !attr.check_name("cfg") { continue; }
+
+            if let Some(items) = attr.meta_item_list() {
+                if let [ref item] = items[..] {
+                    if let Some(Ok(new_cfg)) = item.meta_item().map(Cfg::parse) {
+                        cfg &= new_cfg;
+                    }
+                }
+            }
+        }
+
+        if cfg == Cfg::True { None } else { Some(cfg) }
+    }
+
     pub fn from_ast(diagnostic: &::errors::Handler,
                     attrs: &[ast::Attribute]) -> Attributes {
         let mut doc_strings = vec![];
@@ -1103,6 +1122,7 @@ fn external_generic_args(cx: &DocContext<'_, '_, '_>, trait_did: Option<DefId>,
                     return GenericArgs::AngleBracketed {
                         lifetimes,
                         types: types.clean(cx),
+                        consts: vec![],
                         bindings,
                     }
                 }
@@ -1119,9 +1139,11 @@ fn external_generic_args(cx: &DocContext<'_, '_, '_>, trait_did: Option<DefId>,
             }
         },
         _ => {
+            // FIXME(const_generics): substs do not carry consts yet.
             GenericArgs::AngleBracketed {
                 lifetimes,
                 types: types.clean(cx),
+                consts: vec![],
                 bindings,
             }
         }
@@ -1925,17 +1947,20 @@ pub struct Trait {
     pub bounds: Vec<GenericBound>,
     pub is_spotlight: bool,
     pub is_auto: bool,
+    /// Why the trait cannot be made into an object, empty if it can.
+    pub object_safety_violations: Vec<String>,
 }
 
 impl Clean<Item> for doctree::Trait {
     fn clean(&self, cx: &DocContext<'_, '_, '_>) -> Item {
         let attrs = self.attrs.clean(cx);
         let is_spotlight = attrs.has_doc_flag("spotlight");
+        let def_id = cx.tcx.hir().local_def_id(self.id);
         Item {
             name: Some(self.name.clean(cx)),
             attrs: attrs,
             source: self.whence.clean(cx),
-            def_id: cx.tcx.hir().local_def_id(self.id),
+            def_id,
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
             deprecation: self.depr.clean(cx),
@@ -1947,11 +1972,16 @@ impl Clean<Item> for doctree::Trait {
                 bounds: self.bounds.clean(cx),
                 is_spotlight,
                 is_auto: self.is_auto.clean(cx),
+                object_safety_violations: object_safety_violations(cx, def_id),
             }),
         }
     }
 }
 
+pub fn object_safety_violations(cx: &DocContext<'_, '_, '_>, did: DefId) -> Vec<String> {
+    cx.tcx.object_safety_violations(did).iter().map(|v| v.error_msg().into_owned()).collect()
+}
+
 #[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
 pub struct TraitAlias {
     pub generics: Generics,
@@ -2539,7 +2569,8 @@ impl Clean<Type> for hir::Ty {
                 };
                 let length = match cx.tcx.const_eval(param_env.and(cid)) {
                     Ok(length) => print_const(cx, ty::LazyConst::Evaluated(length)),
-                    Err(_) => "_".to_string(),
+                    // E.g. a const generic parameter, which is shown by name.
+                    Err(_) => print_const_expr(cx, length.body),
                 };
                 Array(box ty.clean(cx), length)
             },
@@ -2965,12 +2996,44 @@ impl Clean<Option<Visibility>> for ty::Visibility {
     }
 }
 
+#[derive(Clone, Copy, RustcEncodable, RustcDecodable, PartialEq, Eq, Debug)]
+pub enum Variance {
+    Covariant,
+    Invariant,
+    Contravariant,
+    Bivariant,
+}
+
+impl Clean<Variance> for ty::Variance {
+    fn clean(&self, _: &DocContext<'_, '_, '_>) -> Variance {
+        match *self {
+            ty::Covariant => Variance::Covariant,
+            ty::Invariant => Variance::Invariant,
+            ty::Contravariant => Variance::Contravariant,
+            ty::Bivariant => Variance::Bivariant,
+        }
+    }
+}
+
+/// The variance of each type parameter of the type `did`, in declaration order.
+pub fn type_param_variances(cx: &DocContext<'_, '_, '_>, did: DefId) -> Vec<(String, Variance)> {
+    let variances = cx.tcx.variances_of(did);
+    cx.tcx.generics_of(did).params.iter().filter_map(|param| match param.kind {
+        ty::GenericParamDefKind::Type { synthetic: None, .. } => {
+            Some((param.name.to_string(), variances[param.index as usize].clean(cx)))
+        }
+        _ => None,
+    }).collect()
+}
+
 #[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
 pub struct Struct {
     pub struct_type: doctree::StructType,
     pub generics: Generics,
     pub fields: Vec<Item>,
     pub fields_stripped: bool,
+    /// The variance of each type parameter.
+    pub variances: Vec<(String, Variance)>,
 }
 
 #[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
@@ -2979,6 +3042,8 @@ pub struct Union {
     pub generics: Generics,
     pub fields: Vec<Item>,
     pub fields_stripped: bool,
+    /// The variance of each type parameter.
+    pub variances: Vec<(String, Variance)>,
 }
 
 impl Clean<Item> for doctree::Struct {
@@ -2996,6 +3061,7 @@ impl Clean<Item> for doctree::Struct {
                 generics: self.generics.clean(cx),
                 fields: self.fields.clean(cx),
                 fields_stripped: false,
+                variances: type_param_variances(cx, cx.tcx.hir().local_def_id(self.id)),
             }),
         }
     }
@@ -3016,6 +3082,7 @@ impl Clean<Item> for doctree::Union {
                 generics: self.generics.clean(cx),
                 fields: self.fields.clean(cx),
                 fields_stripped: false,
+                variances: type_param_variances(cx, cx.tcx.hir().local_def_id(self.id)),
             }),
         }
     }
@@ -3046,6 +3113,8 @@ pub struct Enum {
     pub variants: IndexVec<VariantIdx, Item>,
     pub generics: Generics,
     pub variants_stripped: bool,
+    /// The variance of each type parameter.
+    pub variances: Vec<(String, Variance)>,
 }
 
 impl Clean<Item> for doctree::Enum {
@@ -3062,6 +3131,7 @@ impl Clean<Item> for doctree::Enum {
                 variants: self.variants.iter().map(|v| v.clean(cx)).collect(),
                 generics: self.generics.clean(cx),
                 variants_stripped: false,
+                variances: type_param_variances(cx, cx.tcx.hir().local_def_id(self.id)),
             }),
         }
     }
@@ -3216,6 +3286,7 @@ pub enum GenericArgs {
     AngleBracketed {
         lifetimes: Vec<Lifetime>,
         types: Vec<Type>,
+        consts: Vec<Constant>,
         bindings: Vec<TypeBinding>,
     },
     Parenthesized {
@@ -3233,7 +3304,7 @@ impl Clean<GenericArgs> for hir::GenericArgs {
                 output: if output != Type::Tuple(Vec::new()) { Some(output) } else { None }
             }
         } else {
-            let (mut lifetimes, mut types) = (vec![], vec![]);
+            let (mut lifetimes, mut types, mut consts) = (vec![], vec![], vec![]);
             let mut elided_lifetimes = true;
             for arg in &self.args {
                 match arg {
@@ -3246,14 +3317,15 @@ impl Clean<GenericArgs> for hir::GenericArgs {
                     GenericArg::Type(ty) => {
                         types.push(ty.clean(cx));
                     }
-                    GenericArg::Const(..) => {
-                        unimplemented!() // FIXME(const_generics)
+                    GenericArg::Const(ct) => {
+                        consts.push(ct.clean(cx));
                     }
                 }
             }
             GenericArgs::AngleBracketed {
                 lifetimes: if elided_lifetimes { vec![] } else { lifetimes },
                 types,
+                consts,
                 bindings: self.bindings.clean(cx),
             }
         }
@@ -3307,6 +3379,7 @@ fn strip_path(path: &Path) -> Path {
             args: GenericArgs::AngleBracketed {
                 lifetimes: Vec::new(),
                 types: Vec::new(),
+                consts: Vec::new(),
                 bindings: Vec::new(),
             }
         }
@@ -3458,7 +3531,7 @@ impl Clean<Item> for doctree::Static {
     }
 }
 
-#[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
+#[derive(Clone, RustcEncodable, RustcDecodable, PartialEq, Eq, Debug, Hash)]
 pub struct Constant {
     pub type_: Type,
     pub expr: String,
@@ -85,6 +85,9 @@ pub struct Options {
     /// Whether to display warnings during doc generation or while gathering doctests. By default,
     /// all non-rustdoc-specific lints are allowed when generating docs.
     pub display_warnings: bool,
+    /// Dependencies whose documentation is rendered into the same output directory, so that
+    /// links to their items stay local.
+    pub bundled_crates: Vec<String>,
 
     // Options that alter generated documentation pages
 
@@ -128,6 +131,7 @@ impl fmt::Debug for Options {
             .field("default_passes", &self.default_passes)
             .field("manual_passes", &self.manual_passes)
             .field("display_warnings", &self.display_warnings)
+            .field("bundled_crates", &self.bundled_crates)
             .field("crate_version", &self.crate_version)
             .field("render_options", &self.render_options)
             .finish()
@@ -194,6 +198,33 @@ pub struct RenderOptions {
     pub generate_search_filter: bool,
     /// Option (disabled by default) to generate files used by RLS and some other tools.
     pub generate_redirect_pages: bool,
+    /// Whether to split each section of a module page into stable, unstable and deprecated items.
+    /// `false` by default.
+    pub group_by_stability: bool,
+    /// Whether to render the types in declarations with their full path, e.g. `std::io::Error`
+    /// instead of `Error`. `false` by default.
+    pub qualified_paths: bool,
+    /// Whether to show the number of generic parameters next to each item of a module page.
+    /// `false` by default.
+    pub show_generic_counts: bool,
+    /// Whether to leave the blanket impls of common conversion traits like `From` and `Any` out of
+    /// the pages of types. `false` by default.
+    pub hide_blanket_impls: bool,
+    /// Whether to write `doc-coverage.json`, listing how many words of documentation each public
+    /// item has. `false` by default.
+    pub doc_coverage_json: bool,
+    /// Whether to write `modules.dot`, a graph of the crate's module hierarchy. `false` by
+    /// default.
+    pub module_graph: bool,
+    /// Whether to show `examples/NAME.rs`, next to the crate root, as the examples of the item
+    /// `NAME`. `false` by default.
+    pub item_examples: bool,
+    /// Whether to write `api.txt`, listing the signature of each public item on a line of its
+    /// own. `false` by default.
+    pub api_manifest: bool,
+    /// Whether to note the variance of each type parameter on the pages of structs, enums and
+    /// unions. `false` by default.
+    pub show_variances: bool,
 }
 
 impl Options {
@@ -426,6 +457,7 @@ impl Options {
         let playground_url = matches.opt_str("playground-url");
         let maybe_sysroot = matches.opt_str("sysroot").map(PathBuf::from);
         let display_warnings = matches.opt_present("display-warnings");
+        let bundled_crates = matches.opt_strs("bundle-crate");
         let linker = matches.opt_str("linker").map(PathBuf::from);
         let sort_modules_alphabetically = !matches.opt_present("sort-modules-by-appearance");
         let resource_suffix = matches.opt_str("resource-suffix").unwrap_or_default();
@@ -439,6 +471,15 @@ impl Options {
         let generate_search_filter = !matches.opt_present("disable-per-crate-search");
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
         let generate_redirect_pages = matches.opt_present("generate-redirect-pages");
+        let group_by_stability = matches.opt_present("group-by-stability");
+        let qualified_paths = matches.opt_present("qualified-paths");
+        let show_generic_counts = matches.opt_present("show-generic-counts");
+        let hide_blanket_impls = matches.opt_present("hide-blanket-impls");
+        let doc_coverage_json = matches.opt_present("doc-coverage-json");
+        let module_graph = matches.opt_present("module-graph");
+        let item_examples = matches.opt_present("item-examples");
+        let api_manifest = matches.opt_present("api-manifest");
+        let show_variances = matches.opt_present("show-variances");
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -463,6 +504,7 @@ impl Options {
             default_passes,
             manual_passes,
             display_warnings,
+            bundled_crates,
             crate_version,
             persist_doctests,
             render_options: RenderOptions {
@@ -484,6 +526,15 @@ impl Options {
                 markdown_playground_url,
                 generate_search_filter,
                 generate_redirect_pages,
+                group_by_stability,
+                qualified_paths,
+                show_generic_counts,
+                hide_blanket_impls,
+                doc_coverage_json,
+                module_graph,
+                item_examples,
+                api_manifest,
+                show_variances,
             }
         })
     }
@@ -315,7 +315,9 @@ pub fn new_handler(error_format: ErrorOutputType,
     )
 }
 
-pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOptions, Vec<String>) {
+pub fn run_core(options: RustdocOptions)
+    -> (clean::Crate, Vec<clean::Crate>, RenderInfo, RenderOptions, Vec<String>)
+{
     // Parse, resolve, and typecheck the given crate.
 
     let RustdocOptions {
@@ -336,6 +338,7 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
         mut default_passes,
         mut manual_passes,
         display_warnings,
+        bundled_crates,
         render_options,
         ..
     } = options;
@@ -605,16 +608,30 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                 passes::defaults(default_passes).iter().map(|p| p.to_string()).collect();
             passes.extend(manual_passes);
 
+            let mut bundled = Vec::new();
+            for name in &bundled_crates {
+                let cnum = tcx.crates().iter().cloned().find(|&cnum| {
+                    tcx.crate_name(cnum) == &**name
+                });
+                match cnum {
+                    Some(cnum) => bundled.push(clean::inline::build_crate(&ctxt, cnum)),
+                    None => {
+                        ctxt.sess().err(&format!("couldn't find the crate `{}` to bundle", name))
+                    }
+                }
+            }
+
             for pass in &passes {
                 // the "unknown pass" error will be reported when late passes are run
                 if let Some(pass) = passes::find_pass(pass).and_then(|p| p.early_fn()) {
                     krate = pass(krate, &ctxt);
+                    bundled = bundled.into_iter().map(|b| pass(b, &ctxt)).collect();
                 }
             }
 
             ctxt.sess().abort_if_errors();
 
-            (krate, ctxt.renderinfo.into_inner(), render_options, passes)
+            (krate, bundled, ctxt.renderinfo.into_inner(), render_options, passes)
         }), &sess)
     })
 }
@@ -6,6 +6,7 @@
 //! them in the future to instead emit any format desired.
 
 use std::borrow::Cow;
+use std::cell::Cell;
 use std::fmt;
 
 use rustc::hir::def_id::DefId;
@@ -14,9 +15,12 @@ use rustc::hir;
 
 use crate::clean::{self, PrimitiveType};
 use crate::core::DocAccessLevels;
+use crate::html::escape::Escape;
 use crate::html::item_type::ItemType;
 use crate::html::render::{self, cache, CURRENT_LOCATION_KEY};
 
+// Whether types are rendered with their full path, set by `--qualified-paths`.
+thread_local!(pub static QUALIFIED_PATHS: Cell<bool> = Cell::new(false));
 
 /// Helper to render an optional visibility with a space after it (if the
 /// visibility is preset)
@@ -61,6 +65,10 @@ pub struct Function<'a> {
     pub asyncness: hir::IsAsync,
 }
 
+/// The largest number of where-clause predicates that are rendered on the
+/// same line as the signature they belong to.
+const MAX_INLINE_WHERE_PREDICATES: usize = 1;
+
 /// Wrapper struct for emitting a where-clause from Generics.
 pub struct WhereClause<'a>{
     /// The Generics from which to emit a where-clause.
@@ -179,18 +187,23 @@ impl<'a> fmt::Display for WhereClause<'a> {
         if gens.where_predicates.is_empty() {
             return Ok(());
         }
+        // Short clauses read best on the same line as the rest of the
+        // signature; longer ones get one predicate per line.
+        let inline = !end_newline && gens.where_predicates.len() <= MAX_INLINE_WHERE_PREDICATES;
         let mut clause = String::new();
         if f.alternate() {
             clause.push_str(" where");
         } else {
             if end_newline {
                 clause.push_str(" <span class=\"where fmt-newline\">where");
+            } else if inline {
+                clause.push_str(" <span class=\"where fmt-inline\">where");
             } else {
                 clause.push_str(" <span class=\"where\">where");
             }
         }
         for (i, pred) in gens.where_predicates.iter().enumerate() {
-            if f.alternate() {
+            if f.alternate() || inline {
                 clause.push(' ');
             } else {
                 clause.push_str("<br>");
@@ -239,7 +252,9 @@ impl<'a> fmt::Display for WhereClause<'a> {
             }
         }
 
-        if !f.alternate() {
+        if inline && !f.alternate() {
+            clause.push_str("</span>");
+        } else if !f.alternate() {
             clause.push_str("</span>");
             let padding = "&nbsp;".repeat(indent + 4);
             clause = clause.replace("<br>", &format!("<br>{}", padding));
@@ -301,9 +316,10 @@ impl fmt::Display for clean::GenericArgs {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             clean::GenericArgs::AngleBracketed {
-                ref lifetimes, ref types, ref bindings
+                ref lifetimes, ref types, ref consts, ref bindings
             } => {
-                if !lifetimes.is_empty() || !types.is_empty() || !bindings.is_empty() {
+                if !lifetimes.is_empty() || !types.is_empty() || !consts.is_empty() ||
+                   !bindings.is_empty() {
                     if f.alternate() {
                         f.write_str("<")?;
                     } else {
@@ -328,6 +344,17 @@ impl fmt::Display for clean::GenericArgs {
                             write!(f, "{}", *ty)?;
                         }
                     }
+                    for ct in consts {
+                        if comma {
+                            f.write_str(", ")?;
+                        }
+                        comma = true;
+                        if f.alternate() {
+                            f.write_str(&ct.expr)?;
+                        } else {
+                            write!(f, "{}", Escape(&ct.expr))?;
+                        }
+                    }
                     for binding in bindings {
                         if comma {
                             f.write_str(", ")?;
@@ -747,7 +774,7 @@ fn fmt_type(t: &clean::Type, f: &mut fmt::Formatter<'_>, use_absolute: bool) ->
 
 impl fmt::Display for clean::Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt_type(self, f, false)
+        fmt_type(self, f, QUALIFIED_PATHS.with(|slot| slot.get()))
     }
 }
 
@@ -23,6 +23,9 @@ pub struct Page<'a> {
     pub resource_suffix: &'a str,
     pub extra_scripts: &'a [&'a str],
     pub static_extra_scripts: &'a [&'a str],
+    /// The URL of the page search engines should prefer over this one, e.g. the page of a
+    /// re-exported item in the module that defines it.
+    pub canonical_url: Option<&'a str>,
 }
 
 pub fn render<T: fmt::Display, S: fmt::Display>(
@@ -46,6 +49,7 @@ pub fn render<T: fmt::Display, S: fmt::Display>(
     <meta name=\"description\" content=\"{description}\">\
     <meta name=\"keywords\" content=\"{keywords}\">\
     <title>{title}</title>\
+    {canonical}\
     <link rel=\"stylesheet\" type=\"text/css\" href=\"{static_root_path}normalize{suffix}.css\">\
     <link rel=\"stylesheet\" type=\"text/css\" href=\"{static_root_path}rustdoc{suffix}.css\" \
           id=\"mainThemeStyle\">\
@@ -195,6 +199,9 @@ pub fn render<T: fmt::Display, S: fmt::Display>(
         }
     },
     title     = page.title,
+    canonical = page.canonical_url.map(|url| {
+        format!(r#"<link rel="canonical" href="{}">"#, url)
+    }).unwrap_or_default(),
     description = page.description,
     keywords = page.keywords,
     favicon   = if layout.favicon.is_empty() {
@@ -969,7 +969,10 @@ crate fn rust_code_blocks(md: &str) -> Vec<RustCodeBlock> {
                             is_fenced = true;
                             previous_offset + fence_idx
                         }
-                        None => offset,
+                        None => {
+                            is_fenced = false;
+                            offset
+                        }
                     };
                 }
             }
@@ -1028,6 +1031,12 @@ crate fn rust_code_blocks(md: &str) -> Vec<RustCodeBlock> {
     code_blocks
 }
 
+/// Returns the number of fenced code blocks in the markdown that are tagged as `rust` or
+/// untagged, i.e. the number of examples it shows.
+crate fn rust_example_count(md: &str) -> usize {
+    rust_code_blocks(md).iter().filter(|code_block| code_block.is_fenced).count()
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct IdMap {
     map: FxHashMap<String, usize>,
@@ -1087,7 +1096,7 @@ fn test_unique_id() {
 #[cfg(test)]
 mod tests {
     use super::{ErrorCodes, LangString, Markdown, MarkdownHtml, IdMap};
-    use super::plain_summary_line;
+    use super::{plain_summary_line, rust_code_blocks, rust_example_count};
     use std::cell::RefCell;
     use syntax::edition::Edition;
 
@@ -1208,4 +1217,20 @@ mod tests {
         t("Struct<'a, T>", "<p>Struct&lt;'a, T&gt;</p>\n");
         t("Struct<br>", "<p>Struct&lt;br&gt;</p>\n");
     }
+
+    #[test]
+    fn test_rust_code_blocks_fenced() {
+        fn t(input: &str, expect: &[bool]) {
+            let fenced: Vec<_> = rust_code_blocks(input).iter().map(|b| b.is_fenced).collect();
+            assert_eq!(fenced, expect, "original: {}", input);
+        }
+
+        t("```\nlet x = 1;\n```", &[true]);
+        t("    let x = 1;", &[false]);
+        t("```\nlet x = 1;\n```\n\n    let y = 2;", &[true, false]);
+        t("    let x = 1;\n\n```\nlet y = 2;\n```", &[false, true]);
+
+        // Only fenced blocks count as examples.
+        assert_eq!(rust_example_count("```\na\n```\n\n    b\n\n```text\nc\n```"), 1);
+    }
 }
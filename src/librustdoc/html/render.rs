@@ -29,6 +29,7 @@ use std::borrow::Cow;
 use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, VecDeque};
+use std::collections::hash_map::Entry;
 use std::default::Default;
 use std::error;
 use std::fmt::{self, Display, Formatter, Write as FmtWrite};
@@ -63,7 +64,7 @@ use crate::html::escape::Escape;
 use crate::html::format::{AsyncSpace, ConstnessSpace};
 use crate::html::format::{GenericBounds, WhereClause, href, AbiSpace};
 use crate::html::format::{VisSpace, Function, UnsafetySpace, MutableSpace};
-use crate::html::format::fmt_impl_for_trait_page;
+use crate::html::format::{fmt_impl_for_trait_page, QUALIFIED_PATHS};
 use crate::html::item_type::ItemType;
 use crate::html::markdown::{self, Markdown, MarkdownHtml, MarkdownSummaryLine, ErrorCodes, IdMap};
 use crate::html::{highlight, layout, static_files};
@@ -128,6 +129,12 @@ struct SharedContext {
     /// The base-URL of the issue tracker for when an item has been tagged with
     /// an issue number.
     pub issue_tracker_base_url: Option<String>,
+    /// The Markdown source of the changelog given by `#![doc(changelog = "...")]`, rendered
+    /// into `changelog.html` and linked from the crate's sidebar.
+    pub changelog: Option<String>,
+    /// The URL the documentation of the crate is published at, from `#![doc(html_root_url)]`,
+    /// with a trailing slash. Canonical links of pages in the crate are made absolute with it.
+    pub html_root_url: Option<String>,
     /// The given user css file which allow to customize the generated
     /// documentation theme.
     pub css_file_extension: Option<PathBuf>,
@@ -150,6 +157,23 @@ struct SharedContext {
     pub generate_search_filter: bool,
     /// Option disabled by default to generate files used by RLS and some other tools.
     pub generate_redirect_pages: bool,
+    /// This flag indicates whether the items in each section of a module page should be split
+    /// into stable, unstable and deprecated groups.
+    pub group_by_stability: bool,
+    /// This flag indicates whether the number of generic parameters of each item is shown on
+    /// module pages.
+    pub show_generic_counts: bool,
+    /// This flag indicates whether `examples/NAME.rs` in `src_root` is shown on the page of the
+    /// item `NAME`.
+    pub item_examples: bool,
+    /// This flag indicates whether the variance of each type parameter is noted on the pages of
+    /// structs, enums and unions.
+    pub show_variances: bool,
+    /// The item pages written so far, keyed by their lowercased path. Used to warn about pages
+    /// that would overwrite each other on case-insensitive file systems.
+    pub written_pages: RefCell<FxHashMap<String, String>>,
+    /// Pairs of pages whose paths only differ in case.
+    pub page_collisions: RefCell<Vec<(String, String)>>,
 }
 
 impl SharedContext {
@@ -357,11 +381,35 @@ pub struct Cache {
     // folding and add them to the cache later on if we find the trait.
     orphan_trait_impls: Vec<(DefId, FxHashSet<DefId>, Impl)>,
 
+    // Inherent impls for a type that has no page to list them on, because it
+    // is neither a resolved path nor a primitive with a known location. They
+    // are reported once the crate is folded instead of silently vanishing.
+    dropped_impls: Vec<Impl>,
+
     /// Aliases added through `#[doc(alias = "...")]`. Since a few items can have the same alias,
     /// we need the alias element to have an array of items.
     aliases: FxHashMap<String, Vec<IndexItem>>,
+
+    /// The documentation coverage of the public items, if `--doc-coverage-json` asked for it.
+    doc_coverage: Option<Vec<DocCoverageItem>>,
+
+    /// Whether to drop the blanket impls of `HIDDEN_BLANKET_TRAITS`, for `--hide-blanket-impls`.
+    hide_blanket_impls: bool,
 }
 
+/// The traits whose blanket impls `--hide-blanket-impls` leaves out, by their path in the crate
+/// defining them. They are implemented for nearly every type and say little about it.
+const HIDDEN_BLANKET_TRAITS: &[&str] = &[
+    "core::any::Any",
+    "core::borrow::Borrow",
+    "core::borrow::BorrowMut",
+    "core::convert::From",
+    "core::convert::Into",
+    "core::convert::TryFrom",
+    "core::convert::TryInto",
+    "alloc::borrow::ToOwned",
+];
+
 /// Temporary storage for data obtained during `RustdocVisitor::clean()`.
 /// Later on moved into `CACHE_KEY`.
 #[derive(Default)]
@@ -428,6 +476,222 @@ impl ToJson for IndexItem {
     }
 }
 
+/// How much documentation a public item has, for `doc-coverage.json`.
+#[derive(Debug)]
+struct DocCoverageItem {
+    /// The path of the item, or only its name for an item of an impl.
+    path: String,
+    /// The type an item of an impl belongs to. Its path is prefixed to `path` once the whole
+    /// crate is folded, as impls are unnamed and can come before the type.
+    parent: Option<DefId>,
+    ty: ItemType,
+    words: usize,
+}
+
+impl ToJson for DocCoverageItem {
+    fn to_json(&self) -> Json {
+        let mut data = BTreeMap::new();
+        data.insert("path".to_owned(), self.path.to_json());
+        data.insert("kind".to_owned(), self.ty.css_class().to_json());
+        data.insert("documented".to_owned(), (self.words > 0).to_json());
+        data.insert("words".to_owned(), self.words.to_json());
+        Json::Object(data)
+    }
+}
+
+/// Writes the documentation coverage of the crate `krate` to `path`: the number of public items,
+/// how many of them are documented, and how many words each item's documentation has.
+fn write_doc_coverage(path: &Path, krate: &str, items: &[DocCoverageItem]) -> Result<(), Error> {
+    let documented = items.iter().filter(|item| item.words > 0).count();
+    let percentage = if items.is_empty() {
+        100.0
+    } else {
+        documented as f64 * 100.0 / items.len() as f64
+    };
+    let mut report = BTreeMap::new();
+    report.insert("crate".to_owned(), krate.to_json());
+    report.insert("total".to_owned(), items.len().to_json());
+    report.insert("documented".to_owned(), documented.to_json());
+    report.insert("percentage".to_owned(), percentage.to_json());
+    report.insert("items".to_owned(), items.to_json());
+    try_err!(fs::write(path, Json::Object(report).to_string()), path);
+    Ok(())
+}
+
+/// Writes the module hierarchy of a crate to `path` as a DOT graph, given the paths of its
+/// modules, the crate root included. Each node links to the module's page, relative to the crate's
+/// directory.
+fn write_module_graph(path: &Path, modules: &[Vec<String>]) -> Result<(), Error> {
+    let mut dot = String::from("digraph modules {\n");
+    for module in modules {
+        let url = module[1..].iter()
+            .map(|name| format!("{}/", name))
+            .collect::<String>();
+        dot.push_str(&format!("    \"{}\" [label=\"{}\", URL=\"{}index.html\"];\n",
+                              module.join("::"), module.last().unwrap(), url));
+    }
+    for module in modules.iter().filter(|module| module.len() > 1) {
+        dot.push_str(&format!("    \"{}\" -> \"{}\";\n",
+                              module[..module.len() - 1].join("::"), module.join("::")));
+    }
+    dot.push_str("}\n");
+    try_err!(fs::write(path, dot), path);
+    Ok(())
+}
+
+/// Appends the plain text signature of `item` and, for modules, traits and inherent impls, those
+/// of the public items in it to `lines`, for `api.txt`. `path` is the path of the module `item` is
+/// in.
+fn collect_api(item: &clean::Item, path: &mut Vec<String>, lines: &mut Vec<String>) {
+    let qualified = |name: &str| {
+        let mut qualified = path.join("::");
+        if !qualified.is_empty() {
+            qualified.push_str("::");
+        }
+        qualified.push_str(name);
+        qualified
+    };
+    let name = item.name.as_ref().map(|name| qualified(name)).unwrap_or_default();
+    let line = match item.inner {
+        clean::ModuleItem(ref m) => {
+            path.push(item.name.clone().unwrap());
+            // Impls are not public themselves, but their items may be.
+            for item in m.items.iter().filter(|item| {
+                item.visibility == Some(clean::Public) || item.type_() == ItemType::Impl
+            }) {
+                collect_api(item, path, lines);
+            }
+            path.pop();
+            format!("mod {}", name)
+        }
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
+            format!("{}{}{}{:#}fn {}{:#}{:#}{:#}",
+                    ConstnessSpace(f.header.constness),
+                    UnsafetySpace(f.header.unsafety),
+                    AsyncSpace(f.header.asyncness),
+                    AbiSpace(f.header.abi),
+                    name,
+                    f.generics,
+                    f.decl,
+                    WhereClause { gens: &f.generics, indent: 0, end_newline: false })
+        }
+        clean::StructItem(ref s) => {
+            format!("struct {}{:#}{}", name, s.generics, api_fields(s.struct_type, &s.fields,
+                                                                    s.fields_stripped))
+        }
+        clean::UnionItem(ref s) => {
+            format!("union {}{:#}{}", name, s.generics, api_fields(s.struct_type, &s.fields,
+                                                                   s.fields_stripped))
+        }
+        clean::EnumItem(ref e) => {
+            let mut variants = e.variants.iter().map(|variant| {
+                let name = variant.name.as_ref().unwrap();
+                match variant.inner {
+                    clean::VariantItem(clean::Variant {
+                        kind: clean::VariantKind::Tuple(ref tys)
+                    }) => {
+                        let tys = tys.iter().map(|ty| format!("{:#}", ty)).collect::<Vec<_>>();
+                        format!("{}({})", name, tys.join(", "))
+                    }
+                    clean::VariantItem(clean::Variant {
+                        kind: clean::VariantKind::Struct(ref s)
+                    }) => {
+                        format!("{}{}", name, api_fields(s.struct_type, &s.fields,
+                                                         s.fields_stripped))
+                    }
+                    _ => name.clone(),
+                }
+            }).collect::<Vec<_>>();
+            if e.variants_stripped {
+                variants.push("..".to_string());
+            }
+            format!("enum {}{:#} {{ {} }}", name, e.generics, variants.join(", "))
+        }
+        clean::TypedefItem(ref t, _) => {
+            format!("type {}{:#} = {:#}", name, t.generics, t.type_)
+        }
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => {
+            format!("static {}{}: {:#}", MutableSpace(s.mutability), name, s.type_)
+        }
+        clean::ConstantItem(ref c) => format!("const {}: {:#}", name, c.type_),
+        clean::TraitItem(ref t) => {
+            path.push(item.name.clone().unwrap());
+            for item in &t.items {
+                collect_api(item, path, lines);
+            }
+            path.pop();
+            let bounds = if t.bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {:#}", GenericBounds(&t.bounds))
+            };
+            format!("{}trait {}{:#}{}{:#}",
+                    UnsafetySpace(t.unsafety),
+                    name,
+                    t.generics,
+                    bounds,
+                    WhereClause { gens: &t.generics, indent: 0, end_newline: false })
+        }
+        clean::TyMethodItem(clean::TyMethod { ref header, ref decl, ref generics }) |
+        clean::MethodItem(clean::Method { ref header, ref decl, ref generics }) => {
+            format!("{}{}{}{:#}fn {}{:#}{:#}{:#}",
+                    ConstnessSpace(header.constness),
+                    UnsafetySpace(header.unsafety),
+                    AsyncSpace(header.asyncness),
+                    AbiSpace(header.abi),
+                    name,
+                    generics,
+                    decl,
+                    WhereClause { gens: generics, indent: 0, end_newline: false })
+        }
+        clean::AssociatedTypeItem(..) => format!("type {}", name),
+        clean::AssociatedConstItem(ref ty, _) => format!("const {}: {:#}", name, ty),
+        clean::ImplItem(ref i) => {
+            if i.trait_.is_some() {
+                format!("{:#}", i)
+            } else {
+                path.push(format!("{:#}", i.for_));
+                for item in i.items.iter().filter(|item| item.visibility == Some(clean::Public)) {
+                    collect_api(item, path, lines);
+                }
+                path.pop();
+                return;
+            }
+        }
+        clean::MacroItem(..) => format!("macro {}!", name),
+        _ => return,
+    };
+    // Signatures may be split over several lines for the HTML pages.
+    let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    lines.push(line.trim_end_matches(',').to_string());
+}
+
+/// The plain text fields of a struct, union or struct variant, for `api.txt`.
+fn api_fields(struct_type: doctree::StructType, fields: &[clean::Item], stripped: bool) -> String {
+    match struct_type {
+        doctree::Plain => {
+            let mut fields = fields.iter().filter_map(|field| match field.inner {
+                clean::StructFieldItem(ref ty) => {
+                    Some(format!("{}: {:#}", field.name.as_ref().unwrap(), ty))
+                }
+                _ => None,
+            }).collect::<Vec<_>>();
+            if stripped {
+                fields.push("..".to_string());
+            }
+            format!(" {{ {} }}", fields.join(", "))
+        }
+        doctree::Tuple => {
+            let fields = fields.iter().map(|field| match field.inner {
+                clean::StructFieldItem(ref ty) => format!("{:#}", ty),
+                _ => "_".to_string(),
+            }).collect::<Vec<_>>();
+            format!("({})", fields.join(", "))
+        }
+        doctree::Unit => String::new(),
+    }
+}
+
 /// A type used for the search index.
 #[derive(Debug)]
 struct Type {
@@ -498,8 +762,10 @@ pub fn initial_ids() -> Vec<String> {
     ].into_iter().map(|id| (String::from(*id))).collect()
 }
 
-/// Generates the documentation for `crate` into the directory `dst`
+/// Generates the documentation for `crate`, and for the dependencies `bundled` along with it,
+/// into the directory `dst`
 pub fn run(mut krate: clean::Crate,
+           bundled: Vec<clean::Crate>,
            options: RenderOptions,
            passes: FxHashSet<String>,
            renderinfo: RenderInfo,
@@ -519,6 +785,15 @@ pub fn run(mut krate: clean::Crate,
         static_root_path,
         generate_search_filter,
         generate_redirect_pages,
+        group_by_stability,
+        qualified_paths,
+        show_generic_counts,
+        hide_blanket_impls,
+        doc_coverage_json,
+        module_graph,
+        item_examples,
+        api_manifest,
+        show_variances,
         ..
     } = options;
 
@@ -535,6 +810,8 @@ pub fn run(mut krate: clean::Crate,
         include_sources: true,
         local_sources: Default::default(),
         issue_tracker_base_url: None,
+        changelog: None,
+        html_root_url: None,
         layout: layout::Layout {
             logo: String::new(),
             favicon: String::new(),
@@ -549,8 +826,16 @@ pub fn run(mut krate: clean::Crate,
         static_root_path,
         generate_search_filter,
         generate_redirect_pages,
+        group_by_stability,
+        show_generic_counts,
+        item_examples,
+        show_variances,
+        written_pages: Default::default(),
+        page_collisions: Default::default(),
     };
 
+    QUALIFIED_PATHS.with(|slot| slot.set(qualified_paths));
+
     // If user passed in `--playground-url` arg, we fill in crate name here
     if let Some(url) = playground_url {
         markdown::PLAYGROUND.with(|slot| {
@@ -578,9 +863,28 @@ pub fn run(mut krate: clean::Crate,
                 (Some("issue_tracker_base_url"), Some(s)) => {
                     scx.issue_tracker_base_url = Some(s.to_string());
                 }
+                (Some("html_root_url"), Some(s)) => {
+                    let mut url = s.to_string();
+                    if !url.ends_with("/") {
+                        url.push('/');
+                    }
+                    scx.html_root_url = Some(url);
+                }
                 (Some("html_no_source"), None) if attr.is_word() => {
                     scx.include_sources = false;
                 }
+                (Some("changelog"), Some(s)) => {
+                    let path = scx.src_root.join(&*s.as_str());
+                    match fs::read_to_string(&path) {
+                        Ok(changelog) => scx.changelog = Some(changelog),
+                        Err(e) => {
+                            diag.struct_warn(&format!("couldn't read the changelog `{}`: {}",
+                                                      path.display(), e))
+                                .note("no changelog page will be generated")
+                                .emit();
+                        }
+                    }
+                }
                 _ => {}
             }
         }
@@ -630,6 +934,7 @@ pub fn run(mut krate: clean::Crate,
         crate_version: krate.version.take(),
         orphan_impl_items: Vec::new(),
         orphan_trait_impls: Vec::new(),
+        dropped_impls: Vec::new(),
         traits: krate.external_traits.lock().replace(Default::default()),
         deref_trait_did,
         deref_mut_trait_did,
@@ -637,6 +942,8 @@ pub fn run(mut krate: clean::Crate,
         masked_crates: mem::replace(&mut krate.masked_crates, Default::default()),
         typarams: external_typarams,
         aliases: Default::default(),
+        doc_coverage: if doc_coverage_json { Some(Vec::new()) } else { None },
+        hide_blanket_impls,
     };
 
     // Cache where all our extern crates are located
@@ -669,7 +976,18 @@ pub fn run(mut krate: clean::Crate,
         cache.primitive_locations.insert(prim, def_id);
     }
 
-    cache.stack.push(krate.name.clone());
+    // Fold the bundled crates first, so that links to their items lead to their own pages
+    // rather than to copies inlined into the documented crate. Each of them gets its own
+    // search index, so the items they add to it are set aside.
+    let bundled: Vec<_> = bundled.into_iter().map(|b| {
+        cache.stack = vec![b.name.clone()];
+        let b = cache.fold_crate(b);
+        let search_index = mem::replace(&mut cache.search_index, Vec::new());
+        let orphan_impl_items = mem::replace(&mut cache.orphan_impl_items, Vec::new());
+        (b, search_index, orphan_impl_items)
+    }).collect();
+
+    cache.stack = vec![krate.name.clone()];
     krate = cache.fold_crate(krate);
 
     for (trait_did, dids, impl_) in cache.orphan_trait_impls.drain(..) {
@@ -680,8 +998,45 @@ pub fn run(mut krate: clean::Crate,
         }
     }
 
+    for impl_ in cache.dropped_impls.drain(..) {
+        diag.struct_warn(&format!("the methods of `{:#}` are not documented",
+                                  impl_.inner_impl()))
+            .note("the implementing type has no documentation page to list them on")
+            .emit();
+    }
+
     // Build our search index
-    let index = build_index(&krate, &mut cache);
+    let mut indexes = vec![(krate.name.clone(), build_index(&krate, &mut cache))];
+    let bundled: Vec<_> = bundled.into_iter().map(|(b, search_index, orphan_impl_items)| {
+        cache.search_index = search_index;
+        cache.orphan_impl_items = orphan_impl_items;
+        indexes.push((b.name.clone(), build_index(&b, &mut cache)));
+        b
+    }).collect();
+
+    let doc_coverage = cache.doc_coverage.take().map(|items| {
+        // Like orphan impl items in the search index, the items of impls whose type never got
+        // a path are left out.
+        let items = items.into_iter().filter_map(|mut item| {
+            if let Some(did) = item.parent.take() {
+                let &(ref fqp, _) = cache.paths.get(&did)?;
+                item.path = format!("{}::{}", fqp.join("::"), item.path);
+            }
+            Some(item)
+        }).collect::<Vec<_>>();
+        (cx.dst.join(&krate.name).join("doc-coverage.json"), krate.name.clone(), items)
+    });
+
+    let module_graph = if module_graph {
+        let mut modules = cache.paths.iter()
+            .filter(|&(did, &(_, ty))| did.is_local() && ty == ItemType::Module)
+            .map(|(_, &(ref path, _))| path.clone())
+            .collect::<Vec<_>>();
+        modules.sort();
+        Some((cx.dst.join(&krate.name).join("modules.dot"), modules))
+    } else {
+        None
+    };
 
     // Freeze the cache now that the index has been built. Put an Arc into TLS
     // for future parallelization opportunities
@@ -689,10 +1044,46 @@ pub fn run(mut krate: clean::Crate,
     CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
     CURRENT_LOCATION_KEY.with(|s| s.borrow_mut().clear());
 
-    write_shared(&cx, &krate, &*cache, index, &md_opts, diag)?;
+    write_shared(&cx, &krate, &*cache, indexes, &md_opts, diag)?;
+
+    let api_manifest = if api_manifest {
+        let mut lines = vec![];
+        if let Some(ref module) = krate.module {
+            collect_api(module, &mut vec![], &mut lines);
+        }
+        lines.sort();
+        lines.dedup();
+        Some((cx.dst.join(&krate.name).join("api.txt"), lines))
+    } else {
+        None
+    };
 
     // And finally render the whole crate's documentation
-    cx.krate(krate)
+    let shared = cx.shared.clone();
+    for b in bundled {
+        cx.clone().krate(b)?;
+    }
+    cx.krate(krate)?;
+
+    if let Some((path, krate_name, items)) = doc_coverage {
+        write_doc_coverage(&path, &krate_name, &items)?;
+    }
+    if let Some((path, modules)) = module_graph {
+        write_module_graph(&path, &modules)?;
+    }
+    if let Some((path, lines)) = api_manifest {
+        let mut manifest = lines.join("\n");
+        manifest.push('\n');
+        try_err!(fs::write(&path, manifest), &path);
+    }
+
+    for (first, second) in shared.page_collisions.borrow().iter() {
+        diag.struct_warn(&format!("the documentation pages `{}` and `{}` only differ in case",
+                                  first, second))
+            .note("on case-insensitive file systems, one of them overwrites the other")
+            .emit();
+    }
+    Ok(())
 }
 
 /// Builds the search index from the collected metadata
@@ -769,7 +1160,7 @@ fn write_shared(
     cx: &Context,
     krate: &clean::Crate,
     cache: &Cache,
-    search_index: String,
+    search_indexes: Vec<(String, String)>,
     options: &RenderOptions,
     diag: &errors::Handler,
 ) -> Result<(), Error> {
@@ -939,7 +1330,7 @@ themePicker.onblur = handleThemeButtonsBlur;
 
     fn collect(
         path: &Path,
-        krate: &str,
+        replaced: &[&str],
         key: &str,
         for_search_index: bool,
     ) -> io::Result<(Vec<String>, Vec<String>, Vec<String>)> {
@@ -949,7 +1340,7 @@ themePicker.onblur = handleThemeButtonsBlur;
         let mut krates = Vec::new();
         let mut variables = Vec::new();
 
-        let mut krate = krate.to_owned();
+        let mut replaced = replaced.iter().map(|&krate| krate.to_owned()).collect::<Vec<_>>();
 
         if path.exists() {
             for line in BufReader::new(File::open(path)?).lines() {
@@ -963,10 +1354,11 @@ themePicker.onblur = handleThemeButtonsBlur;
                         if let Some((var_pos, Some(value_pos))) =
                                 js::get_variable_name_and_value_positions(&tokens, pos) {
                             if let Some(s) = tokens.0[value_pos].get_string() {
-                                if &s[1..s.len() - 1] == krate {
+                                let value = &s[1..s.len() - 1];
+                                let krate = replaced.iter_mut().find(|k| k.as_str() == value);
+                                if let Some(krate) = krate {
                                     if let Some(var) = tokens[var_pos].get_other() {
-                                        krate = var.to_owned();
-                                        break
+                                        *krate = var.to_owned();
                                     }
                                 }
                             }
@@ -978,7 +1370,9 @@ themePicker.onblur = handleThemeButtonsBlur;
                 if !line.starts_with(key) {
                     continue;
                 }
-                if line.starts_with(&format!(r#"{}["{}"]"#, key, krate)) {
+                if replaced.iter().any(|krate| {
+                    line.starts_with(&format!(r#"{}["{}"]"#, key, krate))
+                }) {
                     continue;
                 }
                 ret.push(line.to_string());
@@ -1090,8 +1484,8 @@ themePicker.onblur = handleThemeButtonsBlur;
         }
 
         let dst = cx.dst.join("source-files.js");
-        let (mut all_sources, _krates, _) = try_err!(collect(&dst, &krate.name, "sourcesIndex",
-                                                             false),
+        let (mut all_sources, _krates, _) = try_err!(collect(&dst, &[&krate.name[..]],
+                                                             "sourcesIndex", false),
                                                      &dst);
         all_sources.push(format!("sourcesIndex[\"{}\"] = {};",
                                  &krate.name,
@@ -1106,11 +1500,15 @@ themePicker.onblur = handleThemeButtonsBlur;
 
     // Update the search index
     let dst = cx.dst.join("search-index.js");
+    let names = search_indexes.iter().map(|&(ref name, _)| &**name).collect::<Vec<_>>();
     let (mut all_indexes, mut krates, variables) = try_err!(collect(&dst,
-                                                                    &krate.name,
+                                                                    &names,
                                                                     "searchIndex",
                                                                     true), &dst);
-    all_indexes.push(search_index);
+    for (name, search_index) in search_indexes {
+        all_indexes.push(search_index);
+        krates.push(name);
+    }
 
     // Sort the indexes by crate so the file will be generated identically even
     // with rustdoc running in parallel.
@@ -1147,8 +1545,8 @@ themePicker.onblur = handleThemeButtonsBlur;
                 resource_suffix: &cx.shared.resource_suffix,
                 extra_scripts: &[],
                 static_extra_scripts: &[],
+                canonical_url: None,
             };
-            krates.push(krate.name.clone());
             krates.sort();
             krates.dedup();
 
@@ -1474,6 +1872,7 @@ impl<'a> SourceCollector<'a> {
             resource_suffix: &self.scx.resource_suffix,
             extra_scripts: &["source-files"],
             static_extra_scripts: &[&format!("source-script{}", self.scx.resource_suffix)],
+            canonical_url: None,
         };
         layout::render(&mut w, &self.scx.layout,
                        &page, &(""), &Source(contents),
@@ -1501,6 +1900,34 @@ impl DocFolder for Cache {
             _ => self.stripped_mod,
         };
 
+        if let Some(ref mut doc_coverage) = self.doc_coverage {
+            if item.def_id.is_local() && !self.stripped_mod &&
+               item.visibility == Some(clean::Public) &&
+               !item.is_import() && !item.is_extern_crate() {
+                // The stack does not contain the type of the impl a method or associated
+                // constant is in, so it is looked up like for the search index below.
+                let parent = match item.inner {
+                    clean::MethodItem(..) | clean::AssociatedConstItem(..) => {
+                        self.parent_stack.last().cloned()
+                    }
+                    _ => None,
+                };
+                let path = match item.name {
+                    Some(ref name) if parent.is_some() => Some(name.clone()),
+                    Some(ref name) if !name.is_empty() => {
+                        Some(format!("{}::{}", self.stack.join("::"), name))
+                    }
+                    _ if item.is_crate() => Some(self.stack.join("::")),
+                    _ => None,
+                };
+                if let Some(path) = path {
+                    let words = item.collapsed_doc_value()
+                        .map_or(0, |doc| doc.split_whitespace().count());
+                    doc_coverage.push(DocCoverageItem { path, parent, ty: item.type_(), words });
+                }
+            }
+        }
+
         // If the impl is from a masked crate or references something from a
         // masked crate then remove it completely.
         if let clean::ImplItem(ref i) = item.inner {
@@ -1627,10 +2054,18 @@ impl DocFolder for Cache {
                 // however, that a re-exported item doesn't show up in the
                 // `public_items` map, so we can skip inserting into the
                 // paths map if there was already an entry present and we're
-                // not a public item.
-                if !self.paths.contains_key(&item.def_id) ||
-                   self.access_levels.is_public(item.def_id)
-                {
+                // not a public item. Of the places a public item shows up
+                // in, the one it is defined in is kept, which is also the
+                // canonical URL of its other pages.
+                let exact_path = self.exact_paths.get(&item.def_id);
+                let replace = match self.paths.get(&item.def_id) {
+                    None => true,
+                    Some(&(ref path, _)) => {
+                        self.access_levels.is_public(item.def_id) &&
+                            exact_path != Some(path)
+                    }
+                };
+                if replace {
                     self.paths.insert(item.def_id,
                                       (self.stack.clone(), item.type_()));
                 }
@@ -1726,7 +2161,12 @@ impl DocFolder for Cache {
                 let impl_item = Impl {
                     impl_item: item,
                 };
-                if impl_item.trait_did().map_or(true, |d| self.traits.contains_key(&d)) {
+                if self.hide_blanket_impls && self.is_hidden_blanket_impl(&impl_item) {
+                    return None;
+                }
+                if impl_item.trait_did().is_none() && dids.is_empty() {
+                    self.dropped_impls.push(impl_item);
+                } else if impl_item.trait_did().map_or(true, |d| self.traits.contains_key(&d)) {
                     for did in dids {
                         self.impls.entry(did).or_insert(vec![]).push(impl_item.clone());
                     }
@@ -1749,6 +2189,16 @@ impl DocFolder for Cache {
 }
 
 impl<'a> Cache {
+    /// Whether `impl_` is a blanket impl of one of `HIDDEN_BLANKET_TRAITS`.
+    fn is_hidden_blanket_impl(&self, impl_: &Impl) -> bool {
+        if impl_.inner_impl().blanket_impl.is_none() {
+            return false;
+        }
+        impl_.trait_did()
+            .and_then(|did| self.external_paths.get(&did).or_else(|| self.paths.get(&did)))
+            .map_or(false, |&(ref fqp, _)| HIDDEN_BLANKET_TRAITS.contains(&&*fqp.join("::")))
+    }
+
     fn generics(&mut self, generics: &clean::Generics) {
         for param in &generics.params {
             match param.kind {
@@ -2042,6 +2492,7 @@ impl Context {
         let settings_file = self.dst.join("settings.html");
 
         let crate_name = krate.name.clone();
+        let is_local = item.def_id.is_local();
         item.name = Some(krate.name);
 
         let mut all = AllTypes::new();
@@ -2072,6 +2523,7 @@ impl Context {
             resource_suffix: &self.shared.resource_suffix,
             extra_scripts: &[],
             static_extra_scripts: &[],
+            canonical_url: None,
         };
         let sidebar = if let Some(ref version) = cache().crate_version {
             format!("<p class='location'>Crate {}</p>\
@@ -2090,6 +2542,27 @@ impl Context {
                                 self.shared.generate_search_filter),
                  &final_file);
 
+        // The changelog belongs to the documented crate, not to the bundled ones.
+        let changelog = if is_local { self.shared.changelog.as_ref() } else { None };
+        if let Some(changelog) = changelog {
+            let changelog_file = self.dst.join(&crate_name).join("changelog.html");
+            page.title = "Changelog";
+            page.description = "Changelog of this crate";
+
+            let mut ids = IdMap::new();
+            let content = format!("<h1 class='fqn'><span class='in-band'>Changelog</span></h1>\
+                                   <div class='docblock'>{}</div>",
+                                  Markdown(changelog, &[], RefCell::new(&mut ids), self.codes));
+            let mut w = BufWriter::new(try_err!(File::create(&changelog_file),
+                                                &changelog_file));
+            try_err!(layout::render(&mut w, &self.shared.layout,
+                                    &page, &sidebar, &content,
+                                    self.shared.css_file_extension.is_some(),
+                                    &self.shared.themes,
+                                    self.shared.generate_search_filter),
+                     &changelog_file);
+        }
+
         // Generating settings page.
         let settings = Settings::new("./", &self.shared.resource_suffix);
         page.title = "Rustdoc settings";
@@ -2144,6 +2617,8 @@ impl Context {
                     it.name.as_ref().unwrap(), tyname, self.shared.layout.krate)
         };
         let keywords = make_item_keywords(it);
+        let url = self.item_url(&self.root_path(), it);
+        let canonical_url = self.canonical_url(it).or_else(|| url.clone());
         let page = layout::Page {
             css_class: tyname,
             root_path: &self.root_path(),
@@ -2154,6 +2629,7 @@ impl Context {
             resource_suffix: &self.shared.resource_suffix,
             extra_scripts: &[],
             static_extra_scripts: &[],
+            canonical_url: canonical_url.as_ref().map(String::as_str),
         };
 
         {
@@ -2168,18 +2644,53 @@ impl Context {
                            self.shared.css_file_extension.is_some(),
                            &self.shared.themes,
                            self.shared.generate_search_filter)?;
-        } else {
-            let mut url = self.root_path();
-            if let Some(&(ref names, ty)) = cache().paths.get(&it.def_id) {
-                for name in &names[..names.len() - 1] {
-                    url.push_str(name);
-                    url.push_str("/");
+        } else if let Some(url) = url {
+            layout::redirect(writer, &url)?;
+        }
+        Ok(())
+    }
+
+    /// The URL of the page `it` is documented on, relative to `root`, the root of the output
+    /// directory. For an item that is re-exported, this is the page in the module that defines
+    /// it, if that is documented.
+    fn item_url(&self, root: &str, it: &clean::Item) -> Option<String> {
+        let cache = cache();
+        let &(ref names, ty) = cache.paths.get(&it.def_id)?;
+        let mut url = root.to_string();
+        for name in &names[..names.len() - 1] {
+            url.push_str(name);
+            url.push_str("/");
+        }
+        url.push_str(&item_path(ty, names.last().unwrap()));
+        Some(url)
+    }
+
+    /// The absolute URL of the page `it` is documented on, if that page is in the documented
+    /// crate and the crate has an `html_root_url`.
+    fn canonical_url(&self, it: &clean::Item) -> Option<String> {
+        let root = self.shared.html_root_url.as_ref()?;
+        let in_crate = cache().paths.get(&it.def_id)
+            .map_or(false, |&(ref names, _)| names[0] == self.shared.layout.krate);
+        if in_crate { self.item_url(root, it) } else { None }
+    }
+
+    /// Notes that the page `file_name` of the current module has been written, and whether
+    /// it clashes with an earlier page on case-insensitive file systems.
+    fn record_page(&self, file_name: &str) {
+        let mut path = self.current.join("/");
+        path.push('/');
+        path.push_str(file_name);
+        let mut written_pages = self.shared.written_pages.borrow_mut();
+        match written_pages.entry(path.to_lowercase()) {
+            Entry::Occupied(entry) => {
+                if *entry.get() != path {
+                    self.shared.page_collisions.borrow_mut().push((entry.get().clone(), path));
                 }
-                url.push_str(&item_path(ty, names.last().unwrap()));
-                layout::redirect(writer, &url)?;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(path);
             }
         }
-        Ok(())
     }
 
     /// Non-parallelized version of rendering an item. This will take the input
@@ -2216,6 +2727,7 @@ impl Context {
                     try_err!(this.shared.ensure_dir(&this.dst), &this.dst);
                     let joint_dst = this.dst.join("index.html");
                     try_err!(fs::write(&joint_dst, buf), &joint_dst);
+                    this.record_page("index.html");
                 }
 
                 let m = match item.inner {
@@ -2250,6 +2762,7 @@ impl Context {
                 try_err!(self.shared.ensure_dir(&self.dst), &self.dst);
                 let joint_dst = self.dst.join(file_name);
                 try_err!(fs::write(&joint_dst, buf), &joint_dst);
+                self.record_page(file_name);
 
                 if !self.render_redirect_pages {
                     all.append(full_path(self, &item), &item_type);
@@ -2367,6 +2880,45 @@ impl<'a> Item<'a> {
     }
 }
 
+/// Renders a compact row with the name of every trait a type implements, linking to the
+/// trait's page. Derived implementations are marked as such.
+fn render_trait_badges(w: &mut fmt::Formatter<'_>, did: DefId) -> fmt::Result {
+    let c = cache();
+    let impls = match c.impls.get(&did) {
+        Some(impls) => impls,
+        None => return Ok(()),
+    };
+    let mut badges: Vec<(String, String, bool)> = impls.iter()
+        .filter(|i| {
+            let impl_ = i.inner_impl();
+            !impl_.synthetic && impl_.blanket_impl.is_none() &&
+                impl_.polarity != Some(clean::ImplPolarity::Negative)
+        })
+        .filter_map(|i| {
+            let (url, _, fqp) = href(i.inner_impl().trait_.def_id()?)?;
+            let derived = i.impl_item.attrs.other_attrs.iter()
+                .any(|attr| attr.check_name("automatically_derived"));
+            Some((fqp.last()?.clone(), url, derived))
+        })
+        .collect();
+    if badges.is_empty() {
+        return Ok(());
+    }
+    badges.sort();
+    badges.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+
+    write!(w, "<div class='trait-badges'>")?;
+    for (name, url, derived) in badges {
+        write!(w, "<a class='trait-badge{}' href='{}' title='{}trait {}'>{}</a>",
+               if derived { " derived" } else { "" },
+               url,
+               if derived { "derived " } else { "" },
+               name,
+               name)?;
+    }
+    write!(w, "</div>")
+}
+
 fn wrap_into_docblock<F>(w: &mut fmt::Formatter<'_>,
                          f: F) -> fmt::Result
 where F: Fn(&mut fmt::Formatter<'_>) -> fmt::Result {
@@ -2452,6 +3004,13 @@ impl<'a> fmt::Display for Item<'a> {
 
         write!(fmt, "</span></h1>")?; // in-band
 
+        match self.item.inner {
+            clean::StructItem(..) | clean::EnumItem(..) => {
+                render_trait_badges(fmt, self.item.def_id)?;
+            }
+            _ => {}
+        }
+
         match self.item.inner {
             clean::ModuleItem(ref m) =>
                 item_module(fmt, self.cx, self.item, &m.items),
@@ -2524,7 +3083,9 @@ fn document(w: &mut fmt::Formatter<'_>, cx: &Context, item: &clean::Item) -> fmt
         info!("Documenting {}", name);
     }
     document_stability(w, cx, item, false)?;
+    document_examples(w, cx, item)?;
     document_full(w, item, cx, "", false)?;
+    document_item_example(w, cx, item)?;
     Ok(())
 }
 
@@ -2593,6 +3154,52 @@ fn document_stability(w: &mut fmt::Formatter<'_>, cx: &Context, item: &clean::It
     Ok(())
 }
 
+fn document_examples(w: &mut fmt::Formatter<'_>, cx: &Context, item: &clean::Item)
+                     -> fmt::Result {
+    let count = match cx.shared.maybe_collapsed_doc_value(item) {
+        Some(s) => markdown::rust_example_count(&s),
+        None => 0,
+    };
+    if count > 0 {
+        write!(w, "<div class='examples-count'>{} example{}</div>",
+               count, if count == 1 { "" } else { "s" })?;
+    }
+    Ok(())
+}
+
+/// Renders `examples/NAME.rs` as the examples of the item `NAME`, with `--item-examples`.
+fn document_item_example(w: &mut fmt::Formatter<'_>, cx: &Context, item: &clean::Item)
+                         -> fmt::Result {
+    if !cx.shared.item_examples || !item.def_id.is_local() {
+        return Ok(());
+    }
+    // Only items with a page of their own.
+    match item.inner {
+        clean::StructFieldItem(..) | clean::VariantItem(..) |
+        clean::MethodItem(..) | clean::TyMethodItem(..) |
+        clean::AssociatedConstItem(..) | clean::AssociatedTypeItem(..) => return Ok(()),
+        _ => {}
+    }
+    let name = match item.name {
+        Some(ref name) => name,
+        None => return Ok(()),
+    };
+    let path = cx.shared.src_root.join("examples").join(format!("{}.rs", name));
+    let example = match fs::read_to_string(&path) {
+        Ok(example) => example,
+        Err(_) => return Ok(()),
+    };
+    let id = cx.derive_id("item-examples".to_string());
+    write!(w, "<h2 id='{0}' class='small-section-header'>\
+                 Examples<a href='#{0}' class='anchor'></a>\
+               </h2>", id)?;
+    write!(w, "<div class='docblock'>{}</div>",
+           highlight::render_with_highlighting(&example,
+                                               Some("rust-example-rendered"),
+                                               None,
+                                               None))
+}
+
 fn document_non_exhaustive_header(item: &clean::Item) -> &str {
     if item.is_non_exhaustive() { " (Non-exhaustive)" } else { "" }
 }
@@ -2692,6 +3299,10 @@ fn item_module(w: &mut fmt::Formatter<'_>, cx: &Context,
     if cx.shared.sort_modules_alphabetically {
         indices.sort_by(|&i1, &i2| cmp(&items[i1], &items[i2], i1, i2));
     }
+    if cx.shared.group_by_stability {
+        // The sort is stable, so each group keeps the order chosen above.
+        indices.sort_by_key(|&i| (reorder(items[i].type_()), StabilityGroup::of(&items[i])));
+    }
     // This call is to remove re-export duplicates in cases such as:
     //
     // ```
@@ -2724,8 +3335,13 @@ fn item_module(w: &mut fmt::Formatter<'_>, cx: &Context,
                                   0
                               }));
 
+    // Re-exports of items from other crates get a section of their own.
+    let (external_reexports, indices): (Vec<usize>, Vec<usize>) =
+        indices.into_iter().partition(|&i| is_external_reexport(&items[i]));
+
     debug!("{:?}", indices);
     let mut curty = None;
+    let mut curgroup = None;
     for &idx in &indices {
         let myitem = &items[idx];
         if myitem.is_stripped() {
@@ -2741,10 +3357,25 @@ fn item_module(w: &mut fmt::Formatter<'_>, cx: &Context,
                 write!(w, "</table>")?;
             }
             curty = myty;
+            curgroup = None;
             let (short, name) = item_ty_to_strs(&myty.unwrap());
             write!(w, "<h2 id='{id}' class='section-header'>\
-                       <a href=\"#{id}\">{name}</a></h2>\n<table>",
+                       <a href=\"#{id}\">{name}</a></h2>\n",
                    id = cx.derive_id(short.to_owned()), name = name)?;
+            if !cx.shared.group_by_stability {
+                write!(w, "<table>")?;
+            }
+        }
+
+        if cx.shared.group_by_stability {
+            let group = StabilityGroup::of(myitem);
+            if curgroup != Some(group) {
+                if curgroup.is_some() {
+                    write!(w, "</table>")?;
+                }
+                curgroup = Some(group);
+                write!(w, "<h3 class='stability-group'>{}</h3>\n<table>", group.name())?;
+            }
         }
 
         match myitem.inner {
@@ -2767,10 +3398,7 @@ fn item_module(w: &mut fmt::Formatter<'_>, cx: &Context,
                 write!(w, "</code></td></tr>")?;
             }
 
-            clean::ImportItem(ref import) => {
-                write!(w, "<tr><td><code>{}{}</code></td></tr>",
-                       VisSpace(&myitem.visibility), *import)?;
-            }
+            clean::ImportItem(ref import) => item_import_row(w, myitem, import)?,
 
             _ => {
                 if myitem.name.is_none() { continue }
@@ -2790,11 +3418,17 @@ fn item_module(w: &mut fmt::Formatter<'_>, cx: &Context,
                     ""
                 };
 
+                let generic_count = if cx.shared.show_generic_counts {
+                    generic_count(myitem)
+                } else {
+                    String::new()
+                };
+
                 let doc_value = myitem.doc_value().unwrap_or("");
                 write!(w, "\
                        <tr class='{stab}{add}module-item'>\
                            <td><a class=\"{class}\" href=\"{href}\" \
-                                  title='{title}'>{name}</a>{unsafety_flag}</td>\
+                                  title='{title}'>{name}</a>{generic_count}{unsafety_flag}</td>\
                            <td class='docblock-short'>{stab_tags}{docs}</td>\
                        </tr>",
                        name = *myitem.name.as_ref().unwrap(),
@@ -2803,6 +3437,7 @@ fn item_module(w: &mut fmt::Formatter<'_>, cx: &Context,
                        class = myitem.type_(),
                        add = add,
                        stab = stab.unwrap_or_else(|| String::new()),
+                       generic_count = generic_count,
                        unsafety_flag = unsafety_flag,
                        href = item_path(myitem.type_(), myitem.name.as_ref().unwrap()),
                        title = [full_path(cx, myitem), myitem.type_().to_string()]
@@ -2822,9 +3457,90 @@ fn item_module(w: &mut fmt::Formatter<'_>, cx: &Context,
     if curty.is_some() {
         write!(w, "</table>")?;
     }
+
+    if !external_reexports.is_empty() {
+        write!(w, "<h2 id='{id}' class='section-header'>\
+                   <a href=\"#{id}\">Re-exports from other crates</a></h2>\n<table>",
+               id = cx.derive_id("external-reexports".to_owned()))?;
+        for &idx in &external_reexports {
+            if let clean::ImportItem(ref import) = items[idx].inner {
+                item_import_row(w, &items[idx], import)?;
+            }
+        }
+        write!(w, "</table>")?;
+    }
     Ok(())
 }
 
+fn item_import_row(w: &mut fmt::Formatter<'_>, item: &clean::Item,
+                   import: &clean::Import) -> fmt::Result {
+    write!(w, "<tr><td><code>{}{}</code></td>", VisSpace(&item.visibility), *import)?;
+    // Point renamed and nested re-exports at where the item lives.
+    if let clean::Import::Simple(_, ref src) = *import {
+        if let Some((url, shortty, fqp)) = src.did.and_then(href) {
+            let path = fqp.join("::");
+            write!(w, "<td class='docblock-short reexport-origin'>Re-export of \
+                       <a class=\"{class}\" href=\"{href}\" \
+                          title='{class} {path}'>{path}</a></td>",
+                   class = shortty, href = url, path = path)?;
+        }
+    }
+    write!(w, "</tr>")
+}
+
+/// The number of generic parameters of `item` for its row on a module page, or an empty string
+/// if it has none. Parameters introduced by `impl Trait` arguments are not counted.
+fn generic_count(item: &clean::Item) -> String {
+    let count = item.inner.generics().map_or(0, |generics| {
+        generics.params.iter().filter(|param| !param.is_synthetic_type_param()).count()
+    });
+    if count == 0 {
+        return String::new();
+    }
+    format!("<span class='generic-count' title='{count} generic parameter{s}'>\
+             &lt;{count}&gt;</span>",
+            count = count, s = if count == 1 { "" } else { "s" })
+}
+
+/// Whether `item` is a `pub use` of an item from another crate.
+fn is_external_reexport(item: &clean::Item) -> bool {
+    let src = match item.inner {
+        clean::ImportItem(clean::Import::Simple(_, ref src)) |
+        clean::ImportItem(clean::Import::Glob(ref src)) => src,
+        _ => return false,
+    };
+    src.did.map_or(false, |did| cache().external_paths.contains_key(&did))
+}
+
+/// The groups items are split into on a module page with `--group-by-stability`, in the order
+/// they are displayed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum StabilityGroup {
+    Stable,
+    Unstable,
+    Deprecated,
+}
+
+impl StabilityGroup {
+    fn of(item: &clean::Item) -> StabilityGroup {
+        if item.deprecation().is_some() {
+            StabilityGroup::Deprecated
+        } else if item.stability.as_ref().map(|s| s.level) == Some(stability::Unstable) {
+            StabilityGroup::Unstable
+        } else {
+            StabilityGroup::Stable
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            StabilityGroup::Stable => "Stable",
+            StabilityGroup::Unstable => "Unstable",
+            StabilityGroup::Deprecated => "Deprecated",
+        }
+    }
+}
+
 /// Render the stability and deprecation tags that are displayed in the item's summary at the
 /// module level.
 fn stability_tags(item: &clean::Item) -> String {
@@ -3021,9 +3737,32 @@ fn item_function(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
               indent: 0,
               asyncness: f.header.asyncness,
            })?;
+    render_impl_trait_return(w, &f.decl)?;
     document(w, cx, it)
 }
 
+/// Spells out the traits an `impl Trait` return type implements, since they are all a caller
+/// can rely on.
+fn render_impl_trait_return(w: &mut fmt::Formatter<'_>, decl: &clean::FnDecl) -> fmt::Result {
+    let bounds = match decl.output {
+        clean::FunctionRetTy::Return(clean::ImplTrait(ref bounds)) => bounds,
+        _ => return Ok(()),
+    };
+    let traits = bounds.iter()
+        .filter(|bound| match **bound {
+            clean::GenericBound::TraitBound(..) => true,
+            clean::GenericBound::Outlives(..) => false,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if traits.is_empty() {
+        return Ok(());
+    }
+    write!(w, "<div class='impl-trait-return'>Returns a type implementing <code>{}</code>\
+               </div>",
+           GenericBounds(&traits))
+}
+
 fn render_implementor(cx: &Context, implementor: &Impl, w: &mut fmt::Formatter<'_>,
                       implementor_dups: &FxHashMap<&str, (DefId, bool)>) -> fmt::Result {
     // If there's already another implementor that has the same abbridged name, use the
@@ -3163,6 +3902,16 @@ fn item_trait(
     // Trait documentation
     document(w, cx, it)?;
 
+    if t.object_safety_violations.is_empty() {
+        write!(w, "<div class='object-safety'>This trait is object safe.</div>")?;
+    } else {
+        write!(w, "<div class='object-safety'>This trait is not object safe because:<ul>")?;
+        for violation in &t.object_safety_violations {
+            write!(w, "<li>{}</li>", Escape(violation))?;
+        }
+        write!(w, "</ul></div>")?;
+    }
+
     fn write_small_section_header(
         w: &mut fmt::Formatter<'_>,
         id: &str,
@@ -3238,6 +3987,17 @@ fn item_trait(
 
     let cache = cache();
 
+    // Implementing an unsafe trait is a promise to uphold its invariants, so say so where
+    // implementors look.
+    let implementors_list = if t.unsafety == hir::Unsafety::Unsafe {
+        "<div class='unsafe-trait-note'>This trait is <code>unsafe</code> to implement: \
+         implementations must be written as <code>unsafe impl</code> and uphold the \
+         invariants documented above.</div>\
+         <div class='item-list' id='implementors-list'>".to_string()
+    } else {
+        "<div class='item-list' id='implementors-list'>".to_string()
+    };
+
     let mut synthetic_types = Vec::new();
 
     if let Some(implementors) = cache.implementors.get(&it.def_id) {
@@ -3287,8 +4047,7 @@ fn item_trait(
             write_loading_content(w, "")?;
         }
 
-        write_small_section_header(w, "implementors", "Implementors",
-                                   "<div class='item-list' id='implementors-list'>")?;
+        write_small_section_header(w, "implementors", "Implementors", &implementors_list)?;
         for implementor in concrete {
             render_implementor(cx, implementor, w, &implementor_dups)?;
         }
@@ -3308,8 +4067,7 @@ fn item_trait(
     } else {
         // even without any implementations to write in, we still want the heading and list, so the
         // implementors javascript file pulled in below has somewhere to write the impls into
-        write_small_section_header(w, "implementors", "Implementors",
-                                   "<div class='item-list' id='implementors-list'>")?;
+        write_small_section_header(w, "implementors", "Implementors", &implementors_list)?;
         write_loading_content(w, "</div>")?;
 
         if t.auto {
@@ -3358,13 +4116,16 @@ fn naive_assoc_href(it: &clean::Item, link: AssocItemLink<'_>) -> String {
 fn assoc_const(w: &mut fmt::Formatter<'_>,
                it: &clean::Item,
                ty: &clean::Type,
-               _default: Option<&String>,
+               default: Option<&String>,
                link: AssocItemLink<'_>) -> fmt::Result {
     write!(w, "{}const <a href='{}' class=\"constant\"><b>{}</b></a>: {}",
            VisSpace(&it.visibility),
            naive_assoc_href(it, link),
            it.name.as_ref().unwrap(),
            ty)?;
+    if let Some(default) = default {
+        write!(w, " = {}", Escape(default))?;
+    }
     Ok(())
 }
 
@@ -3480,7 +4241,10 @@ fn render_assoc_item(w: &mut fmt::Formatter<'_>,
             method(w, item, m.header, &m.generics, &m.decl, link, parent)
         }
         clean::AssociatedConstItem(ref ty, ref default) => {
-            assoc_const(w, item, ty, default.as_ref(), link)
+            // The declaration at the top of a trait page only lists the
+            // signatures; values are shown in the sections below it.
+            let default = if parent == ItemType::Trait { None } else { default.as_ref() };
+            assoc_const(w, item, ty, default, link)
         }
         clean::AssociatedTypeItem(ref bounds, ref default) => {
             assoc_type(w, item, bounds, default.as_ref(), link)
@@ -3504,6 +4268,7 @@ fn item_struct(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
         write!(w, "</pre>")
     })?;
 
+    render_variances(w, cx, &s.variances)?;
     document(w, cx, it)?;
     let mut fields = s.fields.iter().filter_map(|f| {
         match f.inner {
@@ -3540,6 +4305,29 @@ fn item_struct(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
     render_assoc_items(w, cx, it, it.def_id, AssocItemRender::All)
 }
 
+/// Renders a note on the variance of each type parameter of a type, if `--show-variances` was
+/// passed.
+fn render_variances(w: &mut fmt::Formatter<'_>, cx: &Context,
+                    variances: &[(String, clean::Variance)]) -> fmt::Result {
+    if !cx.shared.show_variances || variances.is_empty() {
+        return Ok(());
+    }
+    write!(w, "<div class='variances'>Variance: ")?;
+    for (i, (name, variance)) in variances.iter().enumerate() {
+        if i > 0 {
+            write!(w, ", ")?;
+        }
+        let variance = match *variance {
+            clean::Variance::Covariant => "covariant",
+            clean::Variance::Invariant => "invariant",
+            clean::Variance::Contravariant => "contravariant",
+            clean::Variance::Bivariant => "bivariant",
+        };
+        write!(w, "<code>{}</code> is <span class='variance'>{}</span>", name, variance)?;
+    }
+    write!(w, "</div>")
+}
+
 fn item_union(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
                s: &clean::Union) -> fmt::Result {
     wrap_into_docblock(w, |w| {
@@ -3554,6 +4342,7 @@ fn item_union(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
         write!(w, "</pre>")
     })?;
 
+    render_variances(w, cx, &s.variances)?;
     document(w, cx, it)?;
     let mut fields = s.fields.iter().filter_map(|f| {
         match f.inner {
@@ -3564,6 +4353,11 @@ fn item_union(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
     if fields.peek().is_some() {
         write!(w, "<h2 id='fields' class='fields small-section-header'>
                    Fields<a href='#fields' class='anchor'></a></h2>")?;
+        // All the fields share the same storage, so reading any of them is only as sound
+        // as the caller's knowledge of which one was last written.
+        write!(w, "<div class='union-fields-note'>Reading a field of a union is \
+                   <code>unsafe</code>: the caller must ensure the bytes are valid for the \
+                   field's type, e.g. because that field was the last one written.</div>")?;
         for (field, ty) in fields {
             let name = field.name.as_ref().expect("union field name");
             let id = format!("{}.{}", ItemType::StructField, name);
@@ -3640,6 +4434,7 @@ fn item_enum(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
         write!(w, "</pre>")
     })?;
 
+    render_variances(w, cx, &e.variances)?;
     document(w, cx, it)?;
     if !e.variants.is_empty() {
         write!(w, "<h2 id='variants' class='variants small-section-header'>
@@ -4187,6 +4982,10 @@ fn render_impl(w: &mut fmt::Formatter<'_>, cx: &Context, i: &Impl, link: AssocIt
                     write!(w, "<code id='{}'>", ns_id)?;
                     render_assoc_item(w, item, link.anchor(&id), ItemType::Impl)?;
                     write!(w, "</code>")?;
+                    if let Some(cfg) = item.attrs.cfg_attrs() {
+                        write!(w, "<span class='stab portability'>{}</span>",
+                               cfg.render_short_html())?;
+                    }
                     render_stability_since_raw(w, item.stable_since(), outer_version)?;
                     if let Some(l) = (Item { cx, item }).src_href() {
                         write!(w, "<a class='srclink' href='{}' title='{}'>[src]</a>",
@@ -4345,6 +5144,44 @@ fn item_trait_alias(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
     render_assoc_items(w, cx, it, it.def_id, AssocItemRender::All)
 }
 
+fn render_aliased_methods(w: &mut fmt::Formatter<'_>, t: &clean::Typedef) -> fmt::Result {
+    let did = match t.type_.def_id() {
+        Some(did) => did,
+        None => return Ok(()),
+    };
+    let url = match href(did) {
+        Some((url, ..)) => url,
+        None => return Ok(()),
+    };
+    let c = cache();
+    let impls = match c.impls.get(&did) {
+        Some(impls) => impls,
+        None => return Ok(()),
+    };
+    let mut names: Vec<&String> = impls.iter()
+        .filter(|i| i.inner_impl().trait_.is_none())
+        .flat_map(|i| i.inner_impl().items.iter())
+        .filter(|item| item.is_method() && !item.is_stripped())
+        .filter_map(|item| item.name.as_ref())
+        .collect();
+    if names.is_empty() {
+        return Ok(());
+    }
+    names.sort();
+    names.dedup();
+
+    write!(w, "\
+        <h2 id='aliased-methods' class='small-section-header'>\
+          Methods from {}<a href='#aliased-methods' class='anchor'></a>\
+        </h2>\
+        <ul class='aliased-methods'>", t.type_)?;
+    for name in names {
+        write!(w, "<li><a href='{url}#method.{name}'><code>{name}</code></a></li>",
+               url = url, name = name)?;
+    }
+    write!(w, "</ul>")
+}
+
 fn item_typedef(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
                 t: &clean::Typedef) -> fmt::Result {
     write!(w, "<pre class='rust typedef'>")?;
@@ -4360,8 +5197,10 @@ fn item_typedef(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item,
     // Render any items associated directly to this alias, as otherwise they
     // won't be visible anywhere in the docs. It would be nice to also show
     // associated items from the aliased type (see discussion in #32077), but
-    // we need #14072 to make sense of the generics.
-    render_assoc_items(w, cx, it, it.def_id, AssocItemRender::All)
+    // we need #14072 to make sense of the generics. Until then, link to the
+    // methods of the aliased type on its own page.
+    render_assoc_items(w, cx, it, it.def_id, AssocItemRender::All)?;
+    render_aliased_methods(w, t)
 }
 
 fn item_foreign_type(w: &mut fmt::Formatter<'_>, cx: &Context, it: &clean::Item) -> fmt::Result {
@@ -4406,7 +5245,7 @@ impl<'a> fmt::Display for Sidebar<'a> {
                 it.name.as_ref().unwrap())?;
         }
 
-        if it.is_crate() {
+        if it.is_crate() && it.def_id.is_local() {
             if let Some(ref version) = cache().crate_version {
                 write!(fmt,
                        "<div class='block version'>\
@@ -4420,6 +5259,9 @@ impl<'a> fmt::Display for Sidebar<'a> {
         if it.is_crate() {
             write!(fmt, "<a id='all-types' href='all.html'><p>See all {}'s items</p></a>",
                    it.name.as_ref().expect("crates always have a name"))?;
+            if it.def_id.is_local() && cx.shared.changelog.is_some() {
+                write!(fmt, "<a id='changelog' href='changelog.html'><p>Changelog</p></a>")?;
+            }
         }
         match it.inner {
             clean::StructItem(ref s) => sidebar_struct(fmt, it, s)?,
@@ -4907,11 +5749,16 @@ fn sidebar_module(fmt: &mut fmt::Formatter<'_>, _it: &clean::Item,
     let mut sidebar = String::new();
 
     if items.iter().any(|it| it.type_() == ItemType::ExternCrate ||
-                             it.type_() == ItemType::Import) {
+                             (it.type_() == ItemType::Import && !is_external_reexport(it))) {
         sidebar.push_str(&format!("<li><a href=\"#{id}\">{name}</a></li>",
                                   id = "reexports",
                                   name = "Re-exports"));
     }
+    if items.iter().any(is_external_reexport) {
+        sidebar.push_str(&format!("<li><a href=\"#{id}\">{name}</a></li>",
+                                  id = "external-reexports",
+                                  name = "Re-exports from other crates"));
+    }
 
     // ordering taken from item_module, reorder, where it prioritized elements in a certain order
     // to print its headings
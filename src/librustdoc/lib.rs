@@ -76,6 +76,7 @@ mod theme;
 
 struct Output {
     krate: clean::Crate,
+    bundled: Vec<clean::Crate>,
     renderinfo: html::render::RenderInfo,
     renderopts: config::RenderOptions,
     passes: Vec<String>,
@@ -347,6 +348,57 @@ fn opts() -> Vec<RustcOptGroup> {
                       "generate-redirect-pages",
                       "Generate extra pages to support legacy URLs and tool links")
         }),
+        unstable("group-by-stability", |o| {
+            o.optflag("",
+                      "group-by-stability",
+                      "group the items of each section of a module page by their stability")
+        }),
+        unstable("show-generic-counts", |o| {
+            o.optflag("",
+                      "show-generic-counts",
+                      "show the number of generic parameters of each item on module pages")
+        }),
+        unstable("show-variances", |o| {
+            o.optflag("",
+                      "show-variances",
+                      "note the variance of each type parameter on the pages of types")
+        }),
+        unstable("api-manifest", |o| {
+            o.optflag("",
+                      "api-manifest",
+                      "write `api.txt`, listing the signatures of the crate's public items")
+        }),
+        unstable("item-examples", |o| {
+            o.optflag("",
+                      "item-examples",
+                      "show `examples/NAME.rs` next to the crate root on the page of the item `NAME`")
+        }),
+        unstable("hide-blanket-impls", |o| {
+            o.optflag("",
+                      "hide-blanket-impls",
+                      "leave blanket impls of common traits like `From` and `Any` off type pages")
+        }),
+        unstable("qualified-paths", |o| {
+            o.optflag("",
+                      "qualified-paths",
+                      "render the types in item declarations with their full path")
+        }),
+        unstable("doc-coverage-json", |o| {
+            o.optflag("",
+                      "doc-coverage-json",
+                      "write the documentation coverage of the public items to doc-coverage.json")
+        }),
+        unstable("module-graph", |o| {
+            o.optflag("",
+                      "module-graph",
+                      "write a graph of the crate's modules in the DOT format to modules.dot")
+        }),
+        unstable("bundle-crate", |o| {
+            o.optmulti("",
+                       "bundle-crate",
+                       "render the documentation of a dependency into the same output directory",
+                       "NAME")
+        }),
     ]
 }
 
@@ -392,12 +444,13 @@ fn main_args(args: &[String]) -> isize {
                      options.debugging_options.treat_err_as_bug,
                      options.debugging_options.ui_testing);
     rust_input(options, move |out| {
-        let Output { krate, passes, renderinfo, renderopts } = out;
+        let Output { krate, bundled, passes, renderinfo, renderopts } = out;
         info!("going to format");
         let (error_format, treat_err_as_bug, ui_testing) = diag_opts;
         let diag = core::new_handler(error_format, None, treat_err_as_bug, ui_testing);
         match html::render::run(
             krate,
+            bundled,
             renderopts,
             passes.into_iter().collect(),
             renderinfo,
@@ -431,7 +484,8 @@ where R: 'static + Send,
     let result = rustc_driver::monitor(move || syntax::with_globals(move || {
         let crate_name = options.crate_name.clone();
         let crate_version = options.crate_version.clone();
-        let (mut krate, renderinfo, renderopts, passes) = core::run_core(options);
+        let (mut krate, mut bundled, renderinfo, renderopts, passes) =
+            core::run_core(options);
 
         info!("finished with rustc");
 
@@ -461,10 +515,12 @@ where R: 'static + Send,
 
             // run it
             krate = pass(krate);
+            bundled = bundled.into_iter().map(pass).collect();
         }
 
         tx.send(f(Output {
             krate: krate,
+            bundled,
             renderinfo: renderinfo,
             renderopts,
             passes: passes
@@ -132,6 +132,63 @@ pub use alloc_crate::alloc::*;
 #[derive(Debug, Copy, Clone)]
 pub struct System;
 
+impl System {
+    /// Allocates a block holding `n` values of type `T` followed by
+    /// `extra_tail` trailing bytes, in the style of a C struct ending in a
+    /// flexible array member.
+    ///
+    /// The block is aligned for `T` and the tail starts right after the
+    /// `n`-th element. On success the layout of the whole block is
+    /// returned as well, since it is required to `dealloc` it again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if memory is exhausted, if computing the size of the
+    /// block overflows, or if the block would be zero-sized.
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn alloc_struct_array<T>(&self, n: usize, extra_tail: usize)
+                                 -> Result<(NonNull<T>, Layout), AllocErr> {
+        let array = Layout::array::<T>(n).map_err(|_| AllocErr)?;
+        let tail = Layout::from_size_align(extra_tail, 1).map_err(|_| AllocErr)?;
+        let (layout, _) = array.extend(tail).map_err(|_| AllocErr)?;
+        if layout.size() == 0 {
+            return Err(AllocErr);
+        }
+        unsafe {
+            NonNull::new(GlobalAlloc::alloc(self, layout.clone()))
+                .map(|p| (p.cast(), layout))
+                .ok_or(AllocErr)
+        }
+    }
+
+    /// Allocates a zeroed block suitable for holding `n` values of type `T`,
+    /// like C's `calloc`.
+    ///
+    /// The size of the block is computed before anything is asked of the
+    /// operating system, so an overflowing `n` never reaches it. On unix the
+    /// block comes straight from `calloc` when `T`'s alignment allows it,
+    /// which can skip zeroing pages that are fresh from the kernel.
+    ///
+    /// The block must be freed with `dealloc` and `Layout::array::<T>(n)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if memory is exhausted, if computing the size of the
+    /// block overflows, or if the block would be zero-sized.
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn alloc_array_zeroed<T>(&self, n: usize) -> Result<NonNull<T>, AllocErr> {
+        let layout = Layout::array::<T>(n).map_err(|_| AllocErr)?;
+        if layout.size() == 0 {
+            return Err(AllocErr);
+        }
+        unsafe {
+            NonNull::new(GlobalAlloc::alloc_zeroed(self, layout))
+                .map(NonNull::cast)
+                .ok_or(AllocErr)
+        }
+    }
+}
+
 // The Alloc impl just forwards to the GlobalAlloc impl, which is in `std::sys::*::alloc`.
 #[unstable(feature = "allocator_api", issue = "32838")]
 unsafe impl Alloc for System {
@@ -157,6 +214,24 @@ unsafe impl Alloc for System {
                       new_size: usize) -> Result<NonNull<u8>, AllocErr> {
         NonNull::new(GlobalAlloc::realloc(self, ptr.as_ptr(), layout, new_size)).ok_or(AllocErr)
     }
+
+    // `usable_size` only gets a layout, but how much a block can really hold
+    // is only known once it has been allocated.
+    #[inline]
+    unsafe fn alloc_excess(&mut self, layout: Layout) -> Result<Excess, AllocErr> {
+        let ptr = Alloc::alloc(self, layout.clone())?;
+        Ok(Excess(ptr, ::sys::alloc::usable_size(ptr.as_ptr(), &layout)))
+    }
+
+    #[inline]
+    unsafe fn realloc_excess(&mut self,
+                             ptr: NonNull<u8>,
+                             layout: Layout,
+                             new_size: usize) -> Result<Excess, AllocErr> {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let ptr = Alloc::realloc(self, ptr, layout, new_size)?;
+        Ok(Excess(ptr, ::sys::alloc::usable_size(ptr.as_ptr(), &new_layout)))
+    }
 }
 
 static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
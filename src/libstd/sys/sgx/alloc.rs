@@ -1,6 +1,7 @@
 extern crate dlmalloc;
 
 use alloc::{GlobalAlloc, Layout, System};
+use sys_common::alloc::debug_check_alignment;
 
 use super::waitqueue::SpinMutex;
 
@@ -12,12 +13,12 @@ static DLMALLOC: SpinMutex<dlmalloc::Dlmalloc> = SpinMutex::new(dlmalloc::DLMALL
 unsafe impl GlobalAlloc for System {
     #[inline]
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        DLMALLOC.lock().malloc(layout.size(), layout.align())
+        debug_check_alignment(DLMALLOC.lock().malloc(layout.size(), layout.align()), &layout)
     }
 
     #[inline]
     unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
-        DLMALLOC.lock().calloc(layout.size(), layout.align())
+        debug_check_alignment(DLMALLOC.lock().calloc(layout.size(), layout.align()), &layout)
     }
 
     #[inline]
@@ -30,3 +31,10 @@ unsafe impl GlobalAlloc for System {
         DLMALLOC.lock().realloc(ptr, layout.size(), layout.align(), new_size)
     }
 }
+
+/// Returns how many bytes the block at `ptr`, allocated for `layout`, can
+/// actually hold. dlmalloc does not say, so this is only the requested size.
+#[inline]
+pub unsafe fn usable_size(_ptr: *mut u8, layout: &Layout) -> usize {
+    layout.size()
+}
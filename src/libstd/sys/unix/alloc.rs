@@ -1,30 +1,53 @@
+#[cfg(target_thread_local)]
+use cell::Cell;
+#[cfg(feature = "alloc_trace")]
+use mem;
+#[cfg(feature = "alloc_trace")]
+use os::unix::io::RawFd;
 use ptr;
+#[cfg(any(feature = "alloc_stats", feature = "alloc_trace"))]
+use sync::atomic::Ordering;
+#[cfg(feature = "alloc_stats")]
+use sync::atomic::AtomicUsize;
+#[cfg(feature = "alloc_trace")]
+use sync::atomic::AtomicIsize;
 use libc;
-use sys_common::alloc::{MIN_ALIGN, realloc_fallback};
-use alloc::{GlobalAlloc, Layout, System};
+use sys::os;
+use sys_common::alloc::{MIN_ALIGN, debug_check_alignment, realloc_fallback};
+use alloc::{AllocErr, GlobalAlloc, Layout, System};
 
 #[stable(feature = "alloc_system_type", since = "1.28.0")]
 unsafe impl GlobalAlloc for System {
     #[inline]
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        if layout.align() <= MIN_ALIGN && layout.align() <= layout.size() {
-            libc::malloc(layout.size()) as *mut u8
+        let ptr = if layout.align() <= MIN_ALIGN && layout.align() <= layout.size() {
+            count_malloc_path();
+            debug_check_alignment(check_errno(libc::malloc(layout.size()) as *mut u8), &layout)
         } else {
+            count_aligned_path();
             #[cfg(target_os = "macos")]
             {
                 if layout.align() > (1 << 31) {
+                    set_last_error(libc::EINVAL);
                     return ptr::null_mut()
                 }
             }
-            aligned_malloc(&layout)
-        }
+            debug_check_alignment(aligned_malloc(&layout), &layout)
+        };
+        trace(TRACE_ALLOC, ptr, &layout, ptr::null_mut());
+        ptr
     }
 
     #[inline]
     unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
         if layout.align() <= MIN_ALIGN && layout.align() <= layout.size() {
-            libc::calloc(layout.size(), 1) as *mut u8
+            count_malloc_path();
+            let ptr = debug_check_alignment(check_errno(libc::calloc(layout.size(), 1) as *mut u8),
+                                            &layout);
+            trace(TRACE_ALLOC, ptr, &layout, ptr::null_mut());
+            ptr
         } else {
+            // `alloc` checks the alignment, and traces the allocation.
             let ptr = self.alloc(layout.clone());
             if !ptr.is_null() {
                 ptr::write_bytes(ptr, 0, layout.size());
@@ -34,20 +57,227 @@ unsafe impl GlobalAlloc for System {
     }
 
     #[inline]
-    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        trace(TRACE_DEALLOC, ptr, &layout, ptr::null_mut());
         libc::free(ptr as *mut libc::c_void)
     }
 
     #[inline]
     unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
-        if layout.align() <= MIN_ALIGN && layout.align() <= new_size {
-            libc::realloc(ptr as *mut libc::c_void, new_size) as *mut u8
+        let new_ptr = if layout.align() <= MIN_ALIGN && layout.align() <= new_size {
+            check_errno(libc::realloc(ptr as *mut libc::c_void, new_size) as *mut u8)
         } else {
-            realloc_fallback(self, ptr, layout, new_size)
+            // `libc::realloc` does not keep the alignment, but there is no
+            // need to move the block if it is already big enough.
+            match block_size(ptr) {
+                Some(usable) if new_size <= usable => ptr,
+                // Traced as an allocation and a deallocation.
+                _ => return realloc_fallback(self, ptr, layout, new_size),
+            }
+        };
+        // A failed reallocation leaves the block as it was.
+        if !new_ptr.is_null() {
+            let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+            trace(TRACE_REALLOC, new_ptr, &new_layout, ptr);
         }
+        new_ptr
+    }
+}
+
+// A native thread local, since the `thread_local!` fallback on targets
+// without one allocates, which would re-enter the allocator. Where there
+// is none, failures are not recorded.
+#[cfg(target_thread_local)]
+#[thread_local]
+static LAST_ERROR: Cell<i32> = Cell::new(0);
+
+// How many allocations took the plain `malloc` path and how many needed
+// `aligned_malloc`, for `SystemExt::align_path_stats`.
+#[cfg(feature = "alloc_stats")]
+static MALLOC_PATH: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "alloc_stats")]
+static ALIGNED_PATH: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "alloc_stats")]
+#[inline]
+fn count_malloc_path() {
+    MALLOC_PATH.fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(not(feature = "alloc_stats"))]
+#[inline]
+fn count_malloc_path() {}
+
+/// See `os::unix::alloc::SystemExt::align_path_stats`.
+#[cfg(feature = "alloc_stats")]
+pub fn align_path_stats() -> (usize, usize) {
+    (MALLOC_PATH.load(Ordering::Relaxed), ALIGNED_PATH.load(Ordering::Relaxed))
+}
+
+#[cfg(feature = "alloc_stats")]
+#[inline]
+fn count_aligned_path() {
+    ALIGNED_PATH.fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(not(feature = "alloc_stats"))]
+#[inline]
+fn count_aligned_path() {}
+
+// The kinds of records `SystemExt::enable_trace` writes.
+const TRACE_ALLOC: usize = 1;
+const TRACE_DEALLOC: usize = 2;
+const TRACE_REALLOC: usize = 3;
+
+// The file descriptor trace records are written to, or -1.
+#[cfg(feature = "alloc_trace")]
+static TRACE_FD: AtomicIsize = AtomicIsize::new(-1);
+
+#[cfg(feature = "alloc_trace")]
+#[inline]
+fn trace(kind: usize, ptr: *mut u8, layout: &Layout, old_ptr: *mut u8) {
+    let fd = TRACE_FD.load(Ordering::Relaxed);
+    if fd >= 0 {
+        write_trace_record(fd as RawFd,
+                           [kind, ptr as usize, layout.size(), layout.align(), old_ptr as usize]);
+    }
+}
+
+#[cfg(feature = "alloc_trace")]
+#[cold]
+fn write_trace_record(fd: RawFd, record: [usize; 5]) {
+    // A single `write` of the record, which is shorter than `PIPE_BUF`,
+    // keeps the records of several threads apart on a pipe. If it fails,
+    // e.g. on a full non-blocking pipe, the record is lost.
+    unsafe {
+        libc::write(fd, record.as_ptr() as *const libc::c_void, mem::size_of_val(&record));
+    }
+}
+
+#[cfg(not(feature = "alloc_trace"))]
+#[inline]
+fn trace(_kind: usize, _ptr: *mut u8, _layout: &Layout, _old_ptr: *mut u8) {}
+
+/// Records `errno` for `SystemExt::last_error` if an allocation returned null.
+#[inline]
+fn check_errno(ptr: *mut u8) -> *mut u8 {
+    if ptr.is_null() {
+        set_last_error(os::errno());
+    }
+    ptr
+}
+
+#[cfg(target_thread_local)]
+#[cold]
+fn set_last_error(code: i32) {
+    LAST_ERROR.set(code);
+}
+
+#[cfg(not(target_thread_local))]
+#[inline]
+fn set_last_error(_code: i32) {}
+
+/// See `os::unix::alloc::SystemExt::last_error`.
+#[cfg(target_thread_local)]
+pub fn last_error() -> Option<i32> {
+    match LAST_ERROR.get() {
+        0 => None,
+        code => Some(code),
     }
 }
 
+#[cfg(not(target_thread_local))]
+pub fn last_error() -> Option<i32> {
+    None
+}
+
+/// See `os::unix::alloc::SystemExt::enable_trace`.
+#[cfg(feature = "alloc_trace")]
+pub fn enable_trace(fd: RawFd) {
+    TRACE_FD.store(fd as isize, Ordering::Relaxed);
+}
+
+/// See `os::unix::alloc::SystemExt::disable_trace`.
+#[cfg(feature = "alloc_trace")]
+pub fn disable_trace() {
+    TRACE_FD.store(-1, Ordering::Relaxed);
+}
+
+/// See `os::unix::alloc::SystemExt::reserve`.
+pub unsafe fn reserve(layout: Layout) -> Result<*mut u8, AllocErr> {
+    if layout.size() == 0 || layout.align() > os::page_size() {
+        return Err(AllocErr);
+    }
+    let ptr = libc::mmap(ptr::null_mut(),
+                         layout.size(),
+                         libc::PROT_NONE,
+                         libc::MAP_PRIVATE | libc::MAP_ANON,
+                         -1,
+                         0);
+    if ptr == libc::MAP_FAILED {
+        Err(AllocErr)
+    } else {
+        Ok(ptr as *mut u8)
+    }
+}
+
+/// See `os::unix::alloc::SystemExt::commit`.
+pub unsafe fn commit(ptr: *mut u8, offset: usize, len: usize) -> Result<(), AllocErr> {
+    let page_size = os::page_size();
+    let start = ptr as usize + offset;
+    let page_start = start & !(page_size - 1);
+    let ret = libc::mprotect(page_start as *mut libc::c_void,
+                             len + (start - page_start),
+                             libc::PROT_READ | libc::PROT_WRITE);
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(AllocErr)
+    }
+}
+
+/// See `os::unix::alloc::SystemExt::dealloc_reserved`.
+pub unsafe fn dealloc_reserved(ptr: *mut u8, layout: Layout) {
+    let ret = libc::munmap(ptr as *mut libc::c_void, layout.size());
+    debug_assert_eq!(ret, 0, "failed to unmap a reservation");
+}
+
+/// Returns how many bytes the block at `ptr`, allocated for `layout`, can
+/// actually hold.
+#[inline]
+pub unsafe fn usable_size(ptr: *mut u8, layout: &Layout) -> usize {
+    match block_size(ptr) {
+        Some(size) if size > layout.size() => size,
+        _ => layout.size(),
+    }
+}
+
+/// Returns how many bytes the block at `ptr` can actually hold, if the
+/// platform can tell.
+#[cfg(target_os = "linux")]
+#[inline]
+unsafe fn block_size(ptr: *mut u8) -> Option<usize> {
+    extern {
+        fn malloc_usable_size(ptr: *mut libc::c_void) -> libc::size_t;
+    }
+    Some(malloc_usable_size(ptr as *mut libc::c_void))
+}
+
+#[cfg(target_os = "macos")]
+#[inline]
+unsafe fn block_size(ptr: *mut u8) -> Option<usize> {
+    extern {
+        fn malloc_size(ptr: *const libc::c_void) -> libc::size_t;
+    }
+    Some(malloc_size(ptr as *const libc::c_void))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+#[inline]
+unsafe fn block_size(_ptr: *mut u8) -> Option<usize> {
+    None
+}
+
 #[cfg(any(target_os = "android",
           target_os = "hermit",
           target_os = "redox",
@@ -71,7 +301,7 @@ unsafe fn aligned_malloc(layout: &Layout) -> *mut u8 {
     // [3]: https://bugs.chromium.org/p/chromium/issues/detail?id=138579
     // [4]: https://chromium.googlesource.com/chromium/src/base/+/master/
     //                                       /memory/aligned_memory.cc
-    libc::memalign(layout.align(), layout.size()) as *mut u8
+    check_errno(libc::memalign(layout.align(), layout.size()) as *mut u8)
 }
 
 #[cfg(not(any(target_os = "android",
@@ -83,8 +313,131 @@ unsafe fn aligned_malloc(layout: &Layout) -> *mut u8 {
     let mut out = ptr::null_mut();
     let ret = libc::posix_memalign(&mut out, layout.align(), layout.size());
     if ret != 0 {
+        set_last_error(ret);
         ptr::null_mut()
     } else {
         out as *mut u8
     }
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use alloc::{Layout, System};
+    use os::unix::alloc::SystemExt;
+
+    #[test]
+    fn reserve_commit_grow() {
+        let layout = Layout::from_size_align(16 << 20, 1).unwrap();
+        unsafe {
+            let base = System.reserve(layout).unwrap();
+            assert!(!base.is_null());
+
+            System.commit(base, 0, 4096).unwrap();
+            for i in 0..4096 {
+                *base.add(i) = i as u8;
+            }
+
+            // Growing keeps what was already written in place.
+            System.commit(base, 4096, 64 << 10).unwrap();
+            for i in 4096..(4096 + (64 << 10)) {
+                *base.add(i) = 0xa5;
+            }
+            for i in 0..4096 {
+                assert_eq!(*base.add(i), i as u8);
+            }
+
+            System.dealloc_reserved(base, layout);
+        }
+    }
+
+    #[test]
+    fn reserve_rejects_bad_layouts() {
+        unsafe {
+            assert!(System.reserve(Layout::from_size_align(0, 1).unwrap()).is_err());
+            let huge_align = Layout::from_size_align(1 << 30, 1 << 29).unwrap();
+            assert!(System.reserve(huge_align).is_err());
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc_stats"))]
+mod stats_tests {
+    use alloc::{GlobalAlloc, Layout, System};
+    use os::unix::alloc::SystemExt;
+
+    #[test]
+    fn align_path_stats() {
+        let small = Layout::from_size_align(8, 8).unwrap();
+        let aligned = Layout::from_size_align(8, 64).unwrap();
+        let (malloc_before, aligned_before) = System.align_path_stats();
+        unsafe {
+            for _ in 0..3 {
+                System.dealloc(System.alloc(small), small);
+            }
+            for _ in 0..2 {
+                System.dealloc(System.alloc(aligned), aligned);
+            }
+            System.dealloc(System.alloc_zeroed(small), small);
+        }
+        // Other threads of the test harness may allocate meanwhile.
+        let (malloc_after, aligned_after) = System.align_path_stats();
+        assert!(malloc_after - malloc_before >= 4);
+        assert!(aligned_after - aligned_before >= 2);
+    }
+}
+
+#[cfg(all(test, feature = "alloc_trace"))]
+mod trace_tests {
+    use alloc::{GlobalAlloc, Layout, System};
+    use libc;
+    use mem;
+    use os::unix::alloc::SystemExt;
+
+    #[test]
+    fn trace_records() {
+        let mut fds = [0; 2];
+        unsafe {
+            assert_eq!(libc::pipe(fds.as_mut_ptr()), 0);
+            // Other threads of the test harness allocate too; rather than
+            // blocking them once the pipe is full, drop their records.
+            libc::fcntl(fds[1], libc::F_SETFL, libc::O_NONBLOCK);
+            // Child processes spawned meanwhile may hold on to the write end,
+            // so the pipe is read until it is empty rather than until EOF.
+            libc::fcntl(fds[0], libc::F_SETFL, libc::O_NONBLOCK);
+        }
+
+        let layout = Layout::from_size_align(24, 8).unwrap();
+        System.enable_trace(fds[1]);
+        let (ptr, grown) = unsafe {
+            let ptr = System.alloc(layout);
+            let grown = System.realloc(ptr, layout, 48);
+            System.dealloc(grown, Layout::from_size_align(48, 8).unwrap());
+            (ptr as usize, grown as usize)
+        };
+        System.disable_trace();
+
+        // The records of other threads are interleaved with ours, so the
+        // whole pipe is searched.
+        let mut records = vec![];
+        let mut buf = [[0usize; 5]; 256];
+        loop {
+            let len = unsafe {
+                libc::read(fds[0],
+                           buf.as_mut_ptr() as *mut libc::c_void,
+                           mem::size_of_val(&buf))
+            };
+            if len <= 0 {
+                break;
+            }
+            assert_eq!(len as usize % mem::size_of::<[usize; 5]>(), 0);
+            records.extend_from_slice(&buf[..len as usize / mem::size_of::<[usize; 5]>()]);
+        }
+        // The pipe is not closed: another thread may have loaded the
+        // descriptor before `disable_trace` and still write a record, which
+        // must not end up in a file that reuses the descriptor number.
+
+        assert!(records.contains(&[1, ptr, 24, 8, 0]));
+        assert!(records.contains(&[3, grown, 48, 8, ptr]));
+        assert!(records.contains(&[2, grown, 48, 8, 0]));
+    }
+}
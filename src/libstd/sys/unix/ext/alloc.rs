@@ -0,0 +1,141 @@
+//! Unix-specific extensions to the [`System`] allocator.
+//!
+//! [`System`]: ../../../../std/alloc/struct.System.html
+
+#![unstable(feature = "alloc_system_ext", issue = "0")]
+
+use alloc::{AllocErr, Layout, System};
+#[cfg(feature = "alloc_trace")]
+use os::unix::io::RawFd;
+use sys;
+
+/// Unix-specific extensions to [`alloc::System`].
+///
+/// The methods are also gated behind their own features.
+///
+/// [`alloc::System`]: ../../../../std/alloc/struct.System.html
+#[unstable(feature = "alloc_system_ext", issue = "0")]
+pub trait SystemExt {
+    /// Reserves a range of address space large enough for `layout` without
+    /// backing it with memory.
+    ///
+    /// The range is inaccessible until parts of it are made usable with
+    /// [`commit`], which lets arena-style allocators grow in place without
+    /// ever moving what they have handed out. It must be released with
+    /// [`dealloc_reserved`].
+    ///
+    /// The reservation is page aligned; layouts asking for a larger
+    /// alignment, and zero-sized layouts, are rejected.
+    ///
+    /// [`commit`]: #tymethod.commit
+    /// [`dealloc_reserved`]: #tymethod.dealloc_reserved
+    #[unstable(feature = "alloc_reserve", issue = "0")]
+    unsafe fn reserve(&self, layout: Layout) -> Result<*mut u8, AllocErr>;
+
+    /// Makes `len` bytes starting at `offset` into a reservation readable
+    /// and writable.
+    ///
+    /// Whole pages are committed, so the bytes around the requested range
+    /// that share a page with it become usable as well. Committing a range
+    /// twice is harmless.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`reserve`] and the range must lie
+    /// within the reserved layout.
+    ///
+    /// [`reserve`]: #tymethod.reserve
+    #[unstable(feature = "alloc_reserve", issue = "0")]
+    unsafe fn commit(&self, ptr: *mut u8, offset: usize, len: usize) -> Result<(), AllocErr>;
+
+    /// Releases a whole reservation made by [`reserve`], committed or not.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`reserve`] for this same `layout`.
+    ///
+    /// [`reserve`]: #tymethod.reserve
+    #[unstable(feature = "alloc_reserve", issue = "0")]
+    unsafe fn dealloc_reserved(&self, ptr: *mut u8, layout: Layout);
+
+    /// Returns the OS error code that made the most recent failed allocation
+    /// on this thread fail, or `None` if no allocation has failed on it yet.
+    ///
+    /// The code is the `errno` left by `malloc` and friends, or the error
+    /// returned by `posix_memalign`. Successful allocations leave it alone.
+    /// On targets without native thread-local storage, recording the code
+    /// could itself allocate, so it is not recorded and this always returns
+    /// `None`.
+    #[unstable(feature = "alloc_last_error", issue = "0")]
+    fn last_error(&self) -> Option<i32>;
+
+    /// Returns how many allocations, on all threads, were served by plain
+    /// `malloc` or `calloc` and how many needed `posix_memalign` because
+    /// their alignment is above what `malloc` guarantees for their size.
+    ///
+    /// Only available when the standard library is built with the
+    /// `alloc_stats` feature, since counting costs an atomic operation per
+    /// allocation. Reallocations are not counted.
+    #[cfg(feature = "alloc_stats")]
+    #[unstable(feature = "alloc_stats", issue = "0")]
+    fn align_path_stats(&self) -> (usize, usize);
+
+    /// Starts writing a record of every allocation, deallocation and
+    /// reallocation made through `System`, on any thread, to `fd`.
+    ///
+    /// Each record is five native-endian `usize`s: the kind of operation
+    /// (1 for an allocation, 2 for a deallocation, 3 for a reallocation),
+    /// the pointer, the size and the alignment of the block, and for a
+    /// reallocation the pointer the block had before. Reallocations that
+    /// have to move an over-aligned block are recorded as an allocation and
+    /// a deallocation, and failed reallocations are not recorded. Writing
+    /// the records does not allocate, and records that cannot be written
+    /// are dropped.
+    ///
+    /// Only available when the standard library is built with the
+    /// `alloc_trace` feature.
+    #[cfg(feature = "alloc_trace")]
+    #[unstable(feature = "alloc_trace", issue = "0")]
+    fn enable_trace(&self, fd: RawFd);
+
+    /// Stops writing the records started by [`enable_trace`].
+    ///
+    /// [`enable_trace`]: #tymethod.enable_trace
+    #[cfg(feature = "alloc_trace")]
+    #[unstable(feature = "alloc_trace", issue = "0")]
+    fn disable_trace(&self);
+}
+
+#[unstable(feature = "alloc_system_ext", issue = "0")]
+impl SystemExt for System {
+    unsafe fn reserve(&self, layout: Layout) -> Result<*mut u8, AllocErr> {
+        sys::alloc::reserve(layout)
+    }
+
+    unsafe fn commit(&self, ptr: *mut u8, offset: usize, len: usize) -> Result<(), AllocErr> {
+        sys::alloc::commit(ptr, offset, len)
+    }
+
+    unsafe fn dealloc_reserved(&self, ptr: *mut u8, layout: Layout) {
+        sys::alloc::dealloc_reserved(ptr, layout)
+    }
+
+    fn last_error(&self) -> Option<i32> {
+        sys::alloc::last_error()
+    }
+
+    #[cfg(feature = "alloc_stats")]
+    fn align_path_stats(&self) -> (usize, usize) {
+        sys::alloc::align_path_stats()
+    }
+
+    #[cfg(feature = "alloc_trace")]
+    fn enable_trace(&self, fd: RawFd) {
+        sys::alloc::enable_trace(fd)
+    }
+
+    #[cfg(feature = "alloc_trace")]
+    fn disable_trace(&self) {
+        sys::alloc::disable_trace()
+    }
+}
@@ -27,6 +27,7 @@
 #![doc(cfg(unix))]
 #![allow(missing_docs)]
 
+pub mod alloc;
 pub mod io;
 pub mod ffi;
 pub mod fs;
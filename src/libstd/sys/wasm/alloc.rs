@@ -19,6 +19,7 @@
 extern crate dlmalloc;
 
 use alloc::{GlobalAlloc, Layout, System};
+use sys_common::alloc::debug_check_alignment;
 
 static mut DLMALLOC: dlmalloc::Dlmalloc = dlmalloc::DLMALLOC_INIT;
 
@@ -27,13 +28,13 @@ unsafe impl GlobalAlloc for System {
     #[inline]
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         let _lock = lock::lock();
-        DLMALLOC.malloc(layout.size(), layout.align())
+        debug_check_alignment(DLMALLOC.malloc(layout.size(), layout.align()), &layout)
     }
 
     #[inline]
     unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
         let _lock = lock::lock();
-        DLMALLOC.calloc(layout.size(), layout.align())
+        debug_check_alignment(DLMALLOC.calloc(layout.size(), layout.align()), &layout)
     }
 
     #[inline]
@@ -49,6 +50,13 @@ unsafe impl GlobalAlloc for System {
     }
 }
 
+/// Returns how many bytes the block at `ptr`, allocated for `layout`, can
+/// actually hold. dlmalloc does not say, so this is only the requested size.
+#[inline]
+pub unsafe fn usable_size(_ptr: *mut u8, layout: &Layout) -> usize {
+    layout.size()
+}
+
 #[cfg(target_feature = "atomics")]
 mod lock {
     use arch::wasm32;
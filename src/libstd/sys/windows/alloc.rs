@@ -1,6 +1,9 @@
 use alloc::{GlobalAlloc, Layout, System};
+#[cfg(target_thread_local)]
+use cell::Cell;
+use sync::atomic::{AtomicUsize, Ordering};
 use sys::c;
-use sys_common::alloc::{MIN_ALIGN, realloc_fallback};
+use sys_common::alloc::{MIN_ALIGN, debug_check_alignment, realloc_fallback};
 
 #[repr(C)]
 struct Header(*mut u8);
@@ -15,18 +18,86 @@ unsafe fn align_ptr(ptr: *mut u8, align: usize) -> *mut u8 {
     aligned
 }
 
+/// The process heap, filled in by the first allocation.
+///
+/// The handle never changes for the lifetime of the process, so threads
+/// racing to fill it in all store the same value and relaxed ordering is
+/// enough.
+static HEAP: AtomicUsize = AtomicUsize::new(0);
+
+#[inline]
+fn process_heap() -> c::HANDLE {
+    match HEAP.load(Ordering::Relaxed) {
+        0 => init_process_heap(),
+        heap => heap as c::HANDLE,
+    }
+}
+
+#[cold]
+fn init_process_heap() -> c::HANDLE {
+    let heap = unsafe { c::GetProcessHeap() };
+    HEAP.store(heap as usize, Ordering::Relaxed);
+    heap
+}
+
+// A native thread local, since the `thread_local!` fallback on targets
+// without one allocates, which would re-enter the allocator. Where there
+// is none, failures are not recorded.
+#[cfg(target_thread_local)]
+#[thread_local]
+static LAST_ERROR: Cell<i32> = Cell::new(0);
+
+/// Records a failure for `SystemExt::last_error` if an allocation returned
+/// null.
+///
+/// `HeapAlloc` and `HeapReAlloc` do not call `SetLastError` when they fail,
+/// and running out of memory is the only way for them to fail without
+/// `HEAP_GENERATE_EXCEPTIONS`, so that is what gets recorded.
+#[inline]
+fn check_null(ptr: *mut u8) -> *mut u8 {
+    if ptr.is_null() {
+        set_last_error(c::ERROR_NOT_ENOUGH_MEMORY as i32);
+    }
+    ptr
+}
+
+#[cfg(target_thread_local)]
+#[cold]
+fn set_last_error(code: i32) {
+    LAST_ERROR.set(code);
+}
+
+#[cfg(not(target_thread_local))]
+#[inline]
+fn set_last_error(_code: i32) {}
+
+/// See `os::windows::alloc::SystemExt::last_error`.
+#[cfg(target_thread_local)]
+pub fn last_error() -> Option<i32> {
+    match LAST_ERROR.get() {
+        0 => None,
+        code => Some(code),
+    }
+}
+
+#[cfg(not(target_thread_local))]
+pub fn last_error() -> Option<i32> {
+    None
+}
+
 #[inline]
 unsafe fn allocate_with_flags(layout: Layout, flags: c::DWORD) -> *mut u8 {
     if layout.align() <= MIN_ALIGN {
-        return c::HeapAlloc(c::GetProcessHeap(), flags, layout.size()) as *mut u8
+        let ptr = check_null(c::HeapAlloc(process_heap(), flags, layout.size()) as *mut u8);
+        return debug_check_alignment(ptr, &layout)
     }
 
     let size = layout.size() + layout.align();
-    let ptr = c::HeapAlloc(c::GetProcessHeap(), flags, size);
+    let ptr = c::HeapAlloc(process_heap(), flags, size);
     if ptr.is_null() {
-        ptr as *mut u8
+        check_null(ptr as *mut u8)
     } else {
-        align_ptr(ptr as *mut u8, layout.align())
+        debug_check_alignment(align_ptr(ptr as *mut u8, layout.align()), &layout)
     }
 }
 
@@ -45,12 +116,12 @@ unsafe impl GlobalAlloc for System {
     #[inline]
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
         if layout.align() <= MIN_ALIGN {
-            let err = c::HeapFree(c::GetProcessHeap(), 0, ptr as c::LPVOID);
+            let err = c::HeapFree(process_heap(), 0, ptr as c::LPVOID);
             debug_assert!(err != 0, "Failed to free heap memory: {}",
                           c::GetLastError());
         } else {
             let header = get_header(ptr);
-            let err = c::HeapFree(c::GetProcessHeap(), 0, header.0 as c::LPVOID);
+            let err = c::HeapFree(process_heap(), 0, header.0 as c::LPVOID);
             debug_assert!(err != 0, "Failed to free heap memory: {}",
                           c::GetLastError());
         }
@@ -59,9 +130,29 @@ unsafe impl GlobalAlloc for System {
     #[inline]
     unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
         if layout.align() <= MIN_ALIGN {
-            c::HeapReAlloc(c::GetProcessHeap(), 0, ptr as c::LPVOID, new_size) as *mut u8
+            check_null(c::HeapReAlloc(process_heap(), 0, ptr as c::LPVOID, new_size)
+                       as *mut u8)
         } else {
             realloc_fallback(self, ptr, layout, new_size)
         }
     }
 }
+
+/// Returns how many bytes the block at `ptr`, allocated for `layout`, can
+/// actually hold.
+#[inline]
+pub unsafe fn usable_size(ptr: *mut u8, layout: &Layout) -> usize {
+    // Over-aligned blocks start somewhere inside what `HeapAlloc` returned.
+    let (block, offset) = if layout.align() <= MIN_ALIGN {
+        (ptr, 0)
+    } else {
+        let block = get_header(ptr).0;
+        (block, ptr as usize - block as usize)
+    };
+    let size = c::HeapSize(process_heap(), 0, block as c::LPCVOID);
+    if size == !0 || size - offset < layout.size() {
+        layout.size()
+    } else {
+        size - offset
+    }
+}
@@ -0,0 +1,34 @@
+//! Windows-specific extensions to the [`System`] allocator.
+//!
+//! [`System`]: ../../../../std/alloc/struct.System.html
+
+#![unstable(feature = "alloc_system_ext", issue = "0")]
+
+use alloc::System;
+use sys;
+
+/// Windows-specific extensions to [`alloc::System`].
+///
+/// The methods are also gated behind their own features.
+///
+/// [`alloc::System`]: ../../../../std/alloc/struct.System.html
+#[unstable(feature = "alloc_system_ext", issue = "0")]
+pub trait SystemExt {
+    /// Returns the OS error code that made the most recent failed allocation
+    /// on this thread fail, or `None` if no allocation has failed on it yet.
+    ///
+    /// The heap functions do not report why they failed, so this is always
+    /// `ERROR_NOT_ENOUGH_MEMORY` once an allocation has failed. Successful
+    /// allocations leave it alone. On targets without native thread-local
+    /// storage, recording the code could itself allocate, so it is not
+    /// recorded and this always returns `None`.
+    #[unstable(feature = "alloc_last_error", issue = "0")]
+    fn last_error(&self) -> Option<i32>;
+}
+
+#[unstable(feature = "alloc_system_ext", issue = "0")]
+impl SystemExt for System {
+    fn last_error(&self) -> Option<i32> {
+        sys::alloc::last_error()
+    }
+}
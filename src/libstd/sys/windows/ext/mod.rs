@@ -10,6 +10,7 @@
 #![doc(cfg(windows))]
 #![allow(missing_docs)]
 
+pub mod alloc;
 pub mod ffi;
 pub mod fs;
 pub mod io;
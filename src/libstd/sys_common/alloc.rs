@@ -38,3 +38,69 @@ pub unsafe fn realloc_fallback(
     }
     new_ptr
 }
+
+/// Aborts if `ptr`, just returned by the platform allocator for `layout`, is
+/// not aligned to `layout.align()`.
+///
+/// This catches bugs in the code that aligns blocks by hand, such as
+/// `align_ptr` on Windows, and in the platform's own aligned allocation
+/// functions. The check only exists with debug assertions.
+#[inline]
+pub fn debug_check_alignment(ptr: *mut u8, layout: &Layout) -> *mut u8 {
+    #[cfg(debug_assertions)]
+    {
+        if !is_aligned(ptr, layout.align()) {
+            misaligned(ptr, layout.align());
+        }
+    }
+    ptr
+}
+
+/// Whether `ptr` is null or aligned to `align`.
+#[inline]
+pub fn is_aligned(ptr: *mut u8, align: usize) -> bool {
+    ptr.is_null() || ptr as usize % align == 0
+}
+
+#[cfg(debug_assertions)]
+#[cold]
+fn misaligned(ptr: *mut u8, align: usize) -> ! {
+    rtabort!("the system allocator returned {:p}, which is not aligned to {} bytes", ptr, align)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{GlobalAlloc, Layout, System};
+    use ptr;
+    use super::is_aligned;
+
+    #[test]
+    fn system_alignment() {
+        for shift in 0..13 {
+            let align = 1 << shift;
+            for &size in &[1, align / 2 + 1, align, 3 * align] {
+                let layout = Layout::from_size_align(size, align).unwrap();
+                unsafe {
+                    let ptr = System.alloc(layout);
+                    assert!(!ptr.is_null());
+                    assert_eq!(ptr as usize % layout.align(), 0);
+                    System.dealloc(ptr, layout);
+
+                    let ptr = System.alloc_zeroed(layout);
+                    assert!(!ptr.is_null());
+                    assert_eq!(ptr as usize % layout.align(), 0);
+                    System.dealloc(ptr, layout);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn misaligned_mock() {
+        // What a broken `align_ptr` could hand out for a 64 byte alignment.
+        let broken = (0x1000 + 8) as *mut u8;
+        assert!(!is_aligned(broken, 64));
+        assert!(is_aligned(0x1040 as *mut u8, 64));
+        assert!(is_aligned(ptr::null_mut(), 64));
+    }
+}
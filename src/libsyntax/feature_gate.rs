@@ -471,6 +471,9 @@ declare_features! (
 
     // #[repr(align(X))] on enums
     (active, repr_align_enum, "1.34.0", Some(57996), None),
+
+    // `#![doc(changelog = "...")]`
+    (active, doc_changelog, "1.34.0", None, None),
 );
 
 declare_features! (
@@ -952,6 +955,12 @@ pub const BUILTIN_ATTRIBUTES: &[(&str, AttributeType, AttributeTemplate, Attribu
                                       is just used for rustc unit tests \
                                       and will never be stable",
                                      cfg_fn!(rustc_attrs))),
+    ("rustc_purity", Normal, template!(Word), Gated(Stability::Unstable,
+                                     "rustc_attrs",
+                                     "the `#[rustc_purity]` attribute \
+                                      is just used for rustc unit tests \
+                                      and will never be stable",
+                                     cfg_fn!(rustc_attrs))),
     ("rustc_layout", Normal, template!(List: "field1, field2, ..."),
      Gated(Stability::Unstable,
            "rustc_attrs",
@@ -1653,6 +1662,10 @@ impl<'a> Visitor<'a> for PostExpansionVisitor<'a> {
                     gate_feature_post!(&self, doc_keyword, attr.span,
                         "#[doc(keyword = \"...\")] is experimental"
                     );
+                } else if content.iter().any(|c| c.check_name("changelog")) {
+                    gate_feature_post!(&self, doc_changelog, attr.span,
+                        "#[doc(changelog = \"...\")] is experimental"
+                    );
                 }
             }
         }
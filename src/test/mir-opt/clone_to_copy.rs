@@ -0,0 +1,33 @@
+fn main() {
+    copy_clone();
+    string_clone(&String::new());
+}
+
+fn copy_clone() -> u32 {
+    42u32.clone()
+}
+
+fn string_clone(s: &String) -> String {
+    s.clone()
+}
+
+// END RUST SOURCE
+// START rustc.copy_clone.CloneToCopy.before.mir
+//     bb0: {
+//         ...
+//         _0 = const <u32 as std::clone::Clone>::clone(move _1) -> bb1;
+//     }
+// END rustc.copy_clone.CloneToCopy.before.mir
+// START rustc.copy_clone.CloneToCopy.after.mir
+//     bb0: {
+//         ...
+//         _0 = (*_1);
+//         goto -> bb1;
+//     }
+// END rustc.copy_clone.CloneToCopy.after.mir
+// START rustc.string_clone.CloneToCopy.after.mir
+//     bb0: {
+//         ...
+//         _0 = const <std::string::String as std::clone::Clone>::clone(move _2) -> bb1;
+//     }
+// END rustc.string_clone.CloneToCopy.after.mir
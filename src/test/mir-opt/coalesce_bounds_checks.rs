@@ -0,0 +1,36 @@
+fn main() {
+    same_index(&[1, 2, 3], 1);
+    next_then_this(&[1, 2, 3], 1);
+}
+
+fn same_index(a: &[u32], i: usize) -> (u32, u32) {
+    (a[i], a[i])
+}
+
+fn next_then_this(a: &[u32], i: usize) -> (u32, u32) {
+    (a[i + 1], a[i])
+}
+
+// END RUST SOURCE
+// START rustc.same_index.CoalesceBoundsChecks.after.mir
+// bb0: {
+//     ...
+//     _6 = Lt(_4, _5);
+//     assert(move _6, "index out of bounds: the len is move _5 but the index is _4") -> bb1;
+// }
+// bb1: {
+//     ...
+//     goto -> bb2;
+// }
+// END rustc.same_index.CoalesceBoundsChecks.after.mir
+// START rustc.next_then_this.CoalesceBoundsChecks.after.mir
+// bb1: {
+//     ...
+//     _7 = Lt(_4, _6);
+//     assert(move _7, "index out of bounds: the len is move _6 but the index is _4") -> bb2;
+// }
+// bb2: {
+//     ...
+//     goto -> bb3;
+// }
+// END rustc.next_then_this.CoalesceBoundsChecks.after.mir
@@ -0,0 +1,63 @@
+fn main() {
+    copy4([1, 2, 3, 4]);
+    copy_n([1, 2, 3, 4], 2);
+}
+
+fn copy4(src: [u32; 4]) -> [u32; 4] {
+    let mut dst = [0; 4];
+    let mut i = 0;
+    while i < 4 {
+        dst[i] = src[i];
+        i += 1;
+    }
+    dst
+}
+
+fn copy_n(src: [u32; 4], n: usize) -> [u32; 4] {
+    let mut dst = [0; 4];
+    let mut i = 0;
+    while i < n {
+        dst[i] = src[i];
+        i += 1;
+    }
+    dst
+}
+
+// END RUST SOURCE
+// START rustc.copy4.CollapseCopyLoops.before.mir
+// bb1: {
+//     ...
+//     _5 = Lt(move _6, const 4usize);
+//     ...
+//     switchInt(move _5) -> [false: bb3, otherwise: bb2];
+// }
+// ...
+// _2[_10] = _1[_8];
+// ...
+// END rustc.copy4.CollapseCopyLoops.before.mir
+// START rustc.copy4.CollapseCopyLoops.after.mir
+// bb0: {
+//     ...
+//     _3 = const 0usize;
+//     _2 = _1;
+//     _3 = const 4usize;
+//     goto -> bb1;
+// }
+// bb1: {
+//     ...
+//     _0 = _2;
+//     ...
+//     return;
+// }
+// END rustc.copy4.CollapseCopyLoops.after.mir
+// START rustc.copy_n.CollapseCopyLoops.after.mir
+// bb1: {
+//     ...
+//     _6 = Lt(move _7, move _8);
+//     ...
+//     switchInt(move _6) -> [false: bb3, otherwise: bb2];
+// }
+// ...
+// _3[_11] = _1[_9];
+// ...
+// END rustc.copy_n.CollapseCopyLoops.after.mir
@@ -0,0 +1,65 @@
+// Check that CopyPropagation propagates constants and copies of locals that are assigned once,
+// but not copies of a local that is assigned again afterwards.
+
+fn konst() -> u32 {
+    let x = 5;
+    x
+}
+
+fn copy(a: u32) -> u32 {
+    let x = a;
+    let y = x;
+    y
+}
+
+#[allow(unused_assignments)]
+fn reassigned(a: u32) -> u32 {
+    let mut x = a;
+    let y = x;
+    x = 7; // Don't propagate `x` into the use of `y`.
+    y
+}
+
+fn main() {
+    // Make sure the functions actually get instantiated.
+    konst();
+    copy(0);
+    reassigned(0);
+}
+
+// END RUST SOURCE
+// START rustc.konst.CopyPropagation.before.mir
+// bb0: {
+//     ...
+//     _1 = const 5u32;
+//     _0 = _1;
+//     ...
+//     return;
+// }
+// END rustc.konst.CopyPropagation.before.mir
+// START rustc.konst.CopyPropagation.after.mir
+// bb0: {
+//     ...
+//     _0 = const 5u32;
+//     ...
+//     return;
+// }
+// END rustc.konst.CopyPropagation.after.mir
+// START rustc.copy.CopyPropagation.after.mir
+// bb0: {
+//     ...
+//     _0 = _1;
+//     ...
+//     return;
+// }
+// END rustc.copy.CopyPropagation.after.mir
+// START rustc.reassigned.CopyPropagation.after.mir
+// bb0: {
+//     ...
+//     _3 = _2;
+//     _2 = const 7u32;
+//     _0 = _3;
+//     ...
+//     return;
+// }
+// END rustc.reassigned.CopyPropagation.after.mir
@@ -0,0 +1,52 @@
+fn main() {
+    div7(100);
+    div8(100);
+    div7_signed(100);
+}
+
+fn div7(x: u32) -> u32 {
+    x / 7
+}
+
+fn div8(x: u32) -> u32 {
+    x / 8
+}
+
+fn div7_signed(x: i32) -> i32 {
+    x / 7
+}
+
+// END RUST SOURCE
+// START rustc.div7.DivisionByConstant.before.mir
+// bb1: {
+//     _0 = Div(move _2, const 7u32);
+//     ...
+// }
+// END rustc.div7.DivisionByConstant.before.mir
+// START rustc.div7.DivisionByConstant.after.mir
+// bb1: {
+//     _4 = move _2;
+//     _5 = _4 as u64 (Misc);
+//     _6 = Mul(move _5, const 613566757u64);
+//     _7 = Shr(move _6, const 32u64);
+//     _8 = move _7 as u32 (Misc);
+//     _9 = Sub(_4, _8);
+//     _10 = Shr(move _9, const 1u32);
+//     _11 = Add(move _10, _8);
+//     _0 = Shr(move _11, const 2u32);
+//     ...
+// }
+// END rustc.div7.DivisionByConstant.after.mir
+// START rustc.div8.DivisionByConstant.after.mir
+// bb1: {
+//     _0 = Div(move _2, const 8u32);
+//     ...
+// }
+// END rustc.div8.DivisionByConstant.after.mir
+// START rustc.div7_signed.DivisionByConstant.after.mir
+// bb1: {
+//     ...
+//     _0 = Div(move _2, const 7i32);
+//     ...
+// }
+// END rustc.div7_signed.DivisionByConstant.after.mir
@@ -0,0 +1,53 @@
+fn main() {
+    const_max();
+    cmp_min();
+    float_min();
+    non_const_max(1);
+}
+
+fn const_max() -> i32 {
+    3i32.max(7)
+}
+
+fn cmp_min() -> u8 {
+    std::cmp::min(2u8, 1u8)
+}
+
+fn float_min() -> f64 {
+    1.5f64.min(-0.5)
+}
+
+fn non_const_max(x: i32) -> i32 {
+    x.max(7)
+}
+
+// END RUST SOURCE
+// START rustc.const_max.FoldConstMinMax.before.mir
+// bb0: {
+//     _0 = const std::cmp::Ord::max(const 3i32, const 7i32) -> bb1;
+// }
+// END rustc.const_max.FoldConstMinMax.before.mir
+// START rustc.const_max.FoldConstMinMax.after.mir
+// bb0: {
+//     _0 = const 7i32;
+//     goto -> bb1;
+// }
+// END rustc.const_max.FoldConstMinMax.after.mir
+// START rustc.cmp_min.FoldConstMinMax.after.mir
+// bb0: {
+//     _0 = const 1u8;
+//     goto -> bb1;
+// }
+// END rustc.cmp_min.FoldConstMinMax.after.mir
+// START rustc.float_min.FoldConstMinMax.after.mir
+// bb0: {
+//     _0 = const -0.5f64;
+//     goto -> bb1;
+// }
+// END rustc.float_min.FoldConstMinMax.after.mir
+// START rustc.non_const_max.FoldConstMinMax.after.mir
+// bb0: {
+//     ...
+//     _0 = const std::cmp::Ord::max(move _2, const 7i32) -> bb1;
+// }
+// END rustc.non_const_max.FoldConstMinMax.after.mir
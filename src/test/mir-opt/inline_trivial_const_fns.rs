@@ -0,0 +1,37 @@
+// compile-flags: -C overflow-checks=off
+
+fn main() {
+    caller(4);
+}
+
+fn caller(x: u32) -> u32 {
+    double(x) + not_inlined(x)
+}
+
+const fn double(x: u32) -> u32 {
+    x + x
+}
+
+#[inline(never)]
+const fn not_inlined(x: u32) -> u32 {
+    x + 1
+}
+
+// END RUST SOURCE
+// START rustc.caller.InlineTrivialConstFns.before.mir
+// bb0: {
+//     ...
+//     _2 = const double(move _3) -> bb1;
+// }
+// END rustc.caller.InlineTrivialConstFns.before.mir
+// START rustc.caller.InlineTrivialConstFns.after.mir
+// bb0: {
+//     ...
+//     _2 = move _6;
+//     goto -> bb1;
+// }
+// bb1: {
+//     ...
+//     _4 = const not_inlined(move _5) -> bb2;
+// }
+// END rustc.caller.InlineTrivialConstFns.after.mir
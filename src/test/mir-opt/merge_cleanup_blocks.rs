@@ -0,0 +1,41 @@
+// ignore-wasm32-bare compiled with panic=abort by default
+
+fn main() {
+    distinct(1);
+}
+
+struct D;
+
+impl Drop for D {
+    fn drop(&mut self) {}
+}
+
+struct E;
+
+impl Drop for E {
+    fn drop(&mut self) {}
+}
+
+#[inline(never)]
+fn may_panic(x: u32) {
+    if x == 0 {
+        panic!("zero");
+    }
+}
+
+// The two calls unwind through different drops, which have to stay apart.
+fn distinct(x: u32) {
+    let _d = D;
+    may_panic(x);
+    let _e = E;
+    may_panic(x);
+}
+
+// END RUST SOURCE
+// START rustc.distinct.MergeCleanupBlocks.after.mir
+// ...
+//     drop(_2) -> bb2;
+// ...
+//     drop(_5) -> bb3;
+// ...
+// END rustc.distinct.MergeCleanupBlocks.after.mir
@@ -0,0 +1,47 @@
+fn main() {
+    low_byte(0x1234);
+    high_bit(0x1_2345);
+}
+
+fn low_byte(x: u32) -> u64 {
+    (x & 0xFF) as u64
+}
+
+fn high_bit(x: u32) -> u64 {
+    (x & 0x1_0000) as u64
+}
+
+// END RUST SOURCE
+// START rustc.low_byte.NarrowIntTemps.before.mir
+// bb0: {
+//     ...
+//     _2 = BitAnd(move _3, const 255u32);
+//     ...
+//     _0 = move _2 as u64 (Misc);
+//     ...
+// }
+// END rustc.low_byte.NarrowIntTemps.before.mir
+// START rustc.low_byte.NarrowIntTemps.after.mir
+// let mut _2: u8;
+// ...
+// bb0: {
+//     ...
+//     _4 = move _3 as u8 (Misc);
+//     _2 = BitAnd(move _4, const 255u8);
+//     ...
+//     _5 = _2 as u32 (Misc);
+//     _0 = move _5 as u64 (Misc);
+//     ...
+// }
+// END rustc.low_byte.NarrowIntTemps.after.mir
+// START rustc.high_bit.NarrowIntTemps.after.mir
+// let mut _2: u32;
+// ...
+// bb0: {
+//     ...
+//     _2 = BitAnd(move _3, const 65536u32);
+//     ...
+//     _0 = move _2 as u64 (Misc);
+//     ...
+// }
+// END rustc.high_bit.NarrowIntTemps.after.mir
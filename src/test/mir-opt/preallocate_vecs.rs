@@ -0,0 +1,40 @@
+fn main() {
+    three_pushes();
+    loop_pushes(3);
+}
+
+fn three_pushes() -> Vec<u32> {
+    let mut v = Vec::new();
+    v.push(1);
+    v.push(2);
+    v.push(3);
+    v
+}
+
+fn loop_pushes(n: u32) -> Vec<u32> {
+    let mut v = Vec::new();
+    for i in 0..n {
+        v.push(i);
+    }
+    v
+}
+
+// END RUST SOURCE
+// START rustc.three_pushes.PreallocateVecs.before.mir
+// bb0: {
+//     StorageLive(_1);
+//     _1 = const <std::vec::Vec<T>>::new() -> bb1;
+// }
+// END rustc.three_pushes.PreallocateVecs.before.mir
+// START rustc.three_pushes.PreallocateVecs.after.mir
+// bb0: {
+//     StorageLive(_1);
+//     _1 = const <std::vec::Vec<T>>::with_capacity(const 3usize) -> bb1;
+// }
+// END rustc.three_pushes.PreallocateVecs.after.mir
+// START rustc.loop_pushes.PreallocateVecs.after.mir
+// bb0: {
+//     StorageLive(_2);
+//     _2 = const <std::vec::Vec<T>>::new() -> bb1;
+// }
+// END rustc.loop_pushes.PreallocateVecs.after.mir
@@ -0,0 +1,44 @@
+fn main() {
+    rem8(100);
+    rem7(100);
+    rem8_signed(100);
+}
+
+fn rem8(x: u32) -> u32 {
+    x % 8
+}
+
+fn rem7(x: u32) -> u32 {
+    x % 7
+}
+
+fn rem8_signed(x: i32) -> i32 {
+    x % 8
+}
+
+// END RUST SOURCE
+// START rustc.rem8.RemainderByPowerOfTwo.before.mir
+// bb1: {
+//     _0 = Rem(move _2, const 8u32);
+//     ...
+// }
+// END rustc.rem8.RemainderByPowerOfTwo.before.mir
+// START rustc.rem8.RemainderByPowerOfTwo.after.mir
+// bb1: {
+//     _0 = BitAnd(move _2, const 7u32);
+//     ...
+// }
+// END rustc.rem8.RemainderByPowerOfTwo.after.mir
+// START rustc.rem7.RemainderByPowerOfTwo.after.mir
+// bb1: {
+//     _0 = Rem(move _2, const 7u32);
+//     ...
+// }
+// END rustc.rem7.RemainderByPowerOfTwo.after.mir
+// START rustc.rem8_signed.RemainderByPowerOfTwo.after.mir
+// bb1: {
+//     ...
+//     _0 = Rem(move _2, const 8i32);
+//     ...
+// }
+// END rustc.rem8_signed.RemainderByPowerOfTwo.after.mir
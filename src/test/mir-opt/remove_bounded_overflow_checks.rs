@@ -0,0 +1,43 @@
+fn main() {
+    bounded();
+    unbounded(100);
+}
+
+fn bounded() -> u8 {
+    let mut i = 0u8;
+    while i < 100 {
+        i += 1;
+    }
+    i
+}
+
+fn unbounded(n: u8) -> u8 {
+    let mut i = 0u8;
+    while i < n {
+        i += 1;
+    }
+    i
+}
+
+// END RUST SOURCE
+// START rustc.bounded.RemoveBoundedOverflowChecks.before.mir
+// bb2: {
+//     ...
+//     _5 = CheckedAdd(_1, const 1u8);
+//     assert(!move (_5.1: bool), "attempt to add with overflow") -> bb4;
+// }
+// END rustc.bounded.RemoveBoundedOverflowChecks.before.mir
+// START rustc.bounded.RemoveBoundedOverflowChecks.after.mir
+// bb2: {
+//     ...
+//     _5 = CheckedAdd(_1, const 1u8);
+//     goto -> bb4;
+// }
+// END rustc.bounded.RemoveBoundedOverflowChecks.after.mir
+// START rustc.unbounded.RemoveBoundedOverflowChecks.after.mir
+// bb2: {
+//     ...
+//     _7 = CheckedAdd(_2, const 1u8);
+//     assert(!move (_7.1: bool), "attempt to add with overflow") -> bb4;
+// }
+// END rustc.unbounded.RemoveBoundedOverflowChecks.after.mir
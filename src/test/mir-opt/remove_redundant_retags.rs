@@ -0,0 +1,56 @@
+// compile-flags: -Z mir-emit-retag -Z span_free_formats
+
+fn main() {
+    let x = 5;
+    removes(&x);
+    keeps(&x);
+}
+
+// The inlined `same` retags its argument `_2` again, right after the caller
+// retagged it.
+fn removes(r: &i32) -> bool {
+    same(r, 5)
+}
+
+// Here the call to `opaque` comes in between.
+fn keeps(r: &i32) -> bool {
+    same(r, opaque())
+}
+
+#[inline(always)]
+fn same(x: &i32, n: i32) -> bool {
+    *x == n
+}
+
+#[inline(never)]
+fn opaque() -> i32 {
+    5
+}
+
+// END RUST SOURCE
+// START rustc.removes.RemoveRedundantRetags.before.mir
+// ...
+//         _2 = _1;
+//         Retag(_2);
+// ...
+//         Retag(_2);
+// ...
+// END rustc.removes.RemoveRedundantRetags.before.mir
+// START rustc.removes.RemoveRedundantRetags.after.mir
+// ...
+//         _2 = _1;
+//         Retag(_2);
+// ...
+//         nop;
+// ...
+// END rustc.removes.RemoveRedundantRetags.after.mir
+// START rustc.keeps.RemoveRedundantRetags.after.mir
+// ...
+//         _2 = _1;
+//         Retag(_2);
+//         StorageLive(_3);
+//         _3 = const opaque() -> bb1;
+// ...
+//         Retag(_2);
+// ...
+// END rustc.keeps.RemoveRedundantRetags.after.mir
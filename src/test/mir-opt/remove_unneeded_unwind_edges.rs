@@ -0,0 +1,50 @@
+// ignore-wasm32-bare compiled with panic=abort by default
+
+fn main() {
+    let _d = D;
+    cannot_panic(1);
+    may_panic(1);
+}
+
+struct D;
+
+impl Drop for D {
+    fn drop(&mut self) {}
+}
+
+#[inline(never)]
+fn cannot_panic(x: u32) -> u32 {
+    x.wrapping_add(1)
+}
+
+#[inline(never)]
+fn may_panic(x: u32) -> u32 {
+    if x == 0 {
+        panic!("zero");
+    }
+    x
+}
+
+// END RUST SOURCE
+// START rustc.main.RemoveUnneededUnwindEdges.before.mir
+// bb0: {
+//     ...
+//     _2 = const cannot_panic(const 1u32) -> [return: bb2, unwind: bb3];
+// }
+// ...
+// bb2: {
+//     ...
+//     _3 = const may_panic(const 1u32) -> [return: bb4, unwind: bb3];
+// }
+// END rustc.main.RemoveUnneededUnwindEdges.before.mir
+// START rustc.main.RemoveUnneededUnwindEdges.after.mir
+// bb0: {
+//     ...
+//     _2 = const cannot_panic(const 1u32) -> bb2;
+// }
+// ...
+// bb2: {
+//     ...
+//     _3 = const may_panic(const 1u32) -> [return: bb4, unwind: bb3];
+// }
+// END rustc.main.RemoveUnneededUnwindEdges.after.mir
@@ -0,0 +1,65 @@
+// compile-flags: -Z mir-reorder-statements
+
+fn main() {
+    interleaved(1, 2);
+    let mut z = 3;
+    through_pointer(&mut z, 4);
+}
+
+fn interleaved(a: u32, b: u32) -> u32 {
+    let mut x = a;
+    let mut y = b;
+    x ^= 1;
+    y ^= 2;
+    x &= 3;
+    y &= 4;
+    x | y
+}
+
+fn through_pointer(p: &mut u32, a: u32) -> u32 {
+    let mut x = a;
+    *p ^= 1;
+    x ^= 2;
+    *p &= 3;
+    x &= 4;
+    x
+}
+
+// END RUST SOURCE
+// START rustc.interleaved.ReorderStatements.before.mir
+// bb0: {
+//     StorageLive(_3);
+//     _3 = _1;
+//     StorageLive(_4);
+//     _4 = _2;
+//     _3 = BitXor(_3, const 1u32);
+//     _4 = BitXor(_4, const 2u32);
+//     _3 = BitAnd(_3, const 3u32);
+//     _4 = BitAnd(_4, const 4u32);
+//     ...
+// }
+// END rustc.interleaved.ReorderStatements.before.mir
+// START rustc.interleaved.ReorderStatements.after.mir
+// bb0: {
+//     StorageLive(_3);
+//     _3 = _1;
+//     _3 = BitXor(_3, const 1u32);
+//     _3 = BitAnd(_3, const 3u32);
+//     ...
+//     StorageLive(_4);
+//     _4 = _2;
+//     _4 = BitXor(_4, const 2u32);
+//     _4 = BitAnd(_4, const 4u32);
+//     ...
+// }
+// END rustc.interleaved.ReorderStatements.after.mir
+// START rustc.through_pointer.ReorderStatements.after.mir
+// bb0: {
+//     ...
+//     (*_1) = BitXor((*_1), const 1u32);
+//     _3 = BitXor(_3, const 2u32);
+//     (*_1) = BitAnd((*_1), const 3u32);
+//     _3 = BitAnd(_3, const 4u32);
+//     ...
+// }
+// END rustc.through_pointer.ReorderStatements.after.mir
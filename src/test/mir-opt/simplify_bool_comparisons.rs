@@ -0,0 +1,74 @@
+fn main() {
+    eq_true(true);
+    eq_false(true);
+    ne_true(true);
+    ne_false(true);
+    eq_int(1);
+}
+
+fn eq_true(x: bool) -> bool {
+    x == true
+}
+
+fn eq_false(x: bool) -> bool {
+    x == false
+}
+
+fn ne_true(x: bool) -> bool {
+    x != true
+}
+
+fn ne_false(x: bool) -> bool {
+    x != false
+}
+
+fn eq_int(x: u32) -> bool {
+    x == 1
+}
+
+// END RUST SOURCE
+// START rustc.eq_true.SimplifyBoolComparisons.after.mir
+// bb0: {
+//     ...
+//     _2 = _1;
+//     _0 = move _2;
+//     ...
+//     return;
+// }
+// END rustc.eq_true.SimplifyBoolComparisons.after.mir
+// START rustc.eq_false.SimplifyBoolComparisons.after.mir
+// bb0: {
+//     ...
+//     _2 = _1;
+//     _0 = Not(move _2);
+//     ...
+//     return;
+// }
+// END rustc.eq_false.SimplifyBoolComparisons.after.mir
+// START rustc.ne_true.SimplifyBoolComparisons.after.mir
+// bb0: {
+//     ...
+//     _2 = _1;
+//     _0 = Not(move _2);
+//     ...
+//     return;
+// }
+// END rustc.ne_true.SimplifyBoolComparisons.after.mir
+// START rustc.ne_false.SimplifyBoolComparisons.after.mir
+// bb0: {
+//     ...
+//     _2 = _1;
+//     _0 = move _2;
+//     ...
+//     return;
+// }
+// END rustc.ne_false.SimplifyBoolComparisons.after.mir
+// START rustc.eq_int.SimplifyBoolComparisons.after.mir
+// bb0: {
+//     ...
+//     _2 = _1;
+//     _0 = Eq(move _2, const 1u32);
+//     ...
+//     return;
+// }
+// END rustc.eq_int.SimplifyBoolComparisons.after.mir
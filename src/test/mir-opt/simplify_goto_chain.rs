@@ -0,0 +1,21 @@
+// Test that SimplifyCfg collapses the chain of `goto`s left behind when
+// SimplifyBranches folds a branch on a constant.
+
+fn main() {
+    if false {
+        println!("hello world!");
+    }
+}
+
+// END RUST SOURCE
+// START rustc.main.SimplifyCfg-early-opt.before.mir
+// bb0: {
+//     goto -> bb3;
+// }
+// END rustc.main.SimplifyCfg-early-opt.before.mir
+// START rustc.main.SimplifyCfg-early-opt.after.mir
+// bb0: {
+//     ...
+//     return;
+// }
+// END rustc.main.SimplifyCfg-early-opt.after.mir
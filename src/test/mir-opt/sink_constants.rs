@@ -0,0 +1,68 @@
+fn main() {
+    one_arm(true);
+    both_arms(true);
+}
+
+#[inline(never)]
+fn opaque(x: u32) -> u32 {
+    x
+}
+
+fn one_arm(c: bool) -> u32 {
+    let x = 42;
+    if c {
+        opaque(x)
+    } else {
+        0
+    }
+}
+
+fn both_arms(c: bool) -> u32 {
+    let x = 42;
+    if c {
+        opaque(x)
+    } else {
+        x
+    }
+}
+
+// END RUST SOURCE
+// START rustc.one_arm.SinkConstants.before.mir
+// bb0: {
+//     StorageLive(_2);
+//     _2 = const 42u32;
+//     StorageLive(_3);
+//     _3 = _1;
+//     ...
+// }
+// bb1: {
+//     StorageLive(_4);
+//     _4 = _2;
+//     ...
+// END rustc.one_arm.SinkConstants.before.mir
+// START rustc.one_arm.SinkConstants.after.mir
+// bb0: {
+//     StorageLive(_2);
+//     StorageLive(_3);
+//     _3 = _1;
+//     ...
+// }
+// bb1: {
+//     _2 = const 42u32;
+//     StorageLive(_4);
+//     _4 = _2;
+//     ...
+// END rustc.one_arm.SinkConstants.after.mir
+// START rustc.both_arms.SinkConstants.after.mir
+// bb0: {
+//     StorageLive(_2);
+//     _2 = const 42u32;
+//     StorageLive(_3);
+//     _3 = _1;
+//     ...
+// }
+// bb1: {
+//     StorageLive(_4);
+//     _4 = _2;
+//     ...
+// END rustc.both_arms.SinkConstants.after.mir
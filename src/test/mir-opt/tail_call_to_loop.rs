@@ -0,0 +1,44 @@
+// compile-flags: -Z mir-tail-call-opt
+
+fn main() {
+    fact(5, 1);
+    fact_plain(5);
+}
+
+fn fact(n: u64, acc: u64) -> u64 {
+    if n == 0 {
+        acc
+    } else {
+        fact(n - 1, acc * n)
+    }
+}
+
+fn fact_plain(n: u64) -> u64 {
+    if n == 0 {
+        1
+    } else {
+        n * fact_plain(n - 1)
+    }
+}
+
+// END RUST SOURCE
+// START rustc.fact.TailCallToLoop.before.mir
+// ...
+//     _0 = const fact(move _5, move _8) -> bb5;
+// ...
+// END rustc.fact.TailCallToLoop.before.mir
+// START rustc.fact.TailCallToLoop.after.mir
+// ...
+//     _12 = move _5;
+//     _13 = move _8;
+//     ...
+//     _1 = move _12;
+//     _2 = move _13;
+//     goto -> bb0;
+// ...
+// END rustc.fact.TailCallToLoop.after.mir
+// START rustc.fact_plain.TailCallToLoop.after.mir
+// ...
+//     _5 = const fact_plain(move _6) -> bb4;
+// ...
+// END rustc.fact_plain.TailCallToLoop.after.mir
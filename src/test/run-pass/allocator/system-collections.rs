@@ -0,0 +1,80 @@
+// run-pass
+
+// Check that std collections work end to end on top of `System`, through
+// its `alloc`, `realloc` and `dealloc` paths, and that everything they
+// allocate is given back.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            LIVE.fetch_add(layout.size(), Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            LIVE.fetch_add(layout.size(), Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            LIVE.fetch_sub(layout.size(), Ordering::SeqCst);
+            LIVE.fetch_add(new_size, Ordering::SeqCst);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const BIG: usize = 1 << 20;
+
+fn main() {
+    let baseline = LIVE.load(Ordering::SeqCst);
+
+    // Growing one element at a time goes through `realloc` many times.
+    let mut v = Vec::new();
+    for i in 0..1_000_000u32 {
+        v.push(i);
+    }
+    assert!(v.iter().enumerate().all(|(i, &x)| i as u32 == x));
+
+    let mut map = HashMap::new();
+    for i in 0..100_000u64 {
+        map.insert(i, i * 2);
+    }
+    assert_eq!(map.len(), 100_000);
+    assert!((0..100_000u64).all(|i| map[&i] == i * 2));
+
+    // Too large for the stack, so build it on the heap directly.
+    let mut boxed = vec![0u8; BIG].into_boxed_slice();
+    assert!(boxed.iter().all(|&b| b == 0));
+    boxed[BIG - 1] = 1;
+    assert_eq!(boxed.iter().map(|&b| b as usize).sum::<usize>(), 1);
+
+    assert!(LIVE.load(Ordering::SeqCst) > baseline);
+    drop(v);
+    drop(map);
+    drop(boxed);
+    assert_eq!(LIVE.load(Ordering::SeqCst), baseline);
+}
@@ -0,0 +1,25 @@
+// run-pass
+
+const YES: bool = true;
+const NO: bool = false;
+
+const ONE: usize = YES as usize;
+const ZERO: usize = NO as usize;
+const TWO: usize = (YES as usize) + 1;
+const WIDE: u64 = (YES as u64) << 40;
+
+static ARRAY_ONE: [u8; YES as usize] = [7];
+static ARRAY_ZERO: [u8; NO as usize] = [];
+
+pub fn main() {
+    assert_eq!(ONE, 1);
+    assert_eq!(ZERO, 0);
+    assert_eq!(TWO, 2);
+    assert_eq!(WIDE, 1 << 40);
+    assert_eq!(ARRAY_ONE.len(), 1);
+    assert_eq!(ARRAY_ZERO.len(), 0);
+    let arr = [0u32; (NO as usize) + 1];
+    assert_eq!(arr.len(), 1);
+    let arr = [0u32; TWO];
+    assert_eq!(arr.len(), 2);
+}
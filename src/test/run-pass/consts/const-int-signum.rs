@@ -0,0 +1,18 @@
+// run-pass
+
+#![feature(const_int_signum)]
+
+const NEG: i32 = (-5i32).signum();
+const ZERO: i8 = 0i8.signum();
+const POS: i64 = 42i64.signum();
+const MIN: i128 = i128::min_value().signum();
+const MAX: isize = isize::max_value().signum();
+
+fn main() {
+    assert_eq!(NEG, -1);
+    assert_eq!(ZERO, 0);
+    assert_eq!(POS, 1);
+    assert_eq!(MIN, -1);
+    assert_eq!(MAX, 1);
+    let _: [(); POS as usize] = [(); 1];
+}
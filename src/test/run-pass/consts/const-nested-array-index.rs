@@ -0,0 +1,14 @@
+// run-pass
+
+const M: [[u8; 2]; 2] = [[1, 2], [3, 4]];
+
+const M01: u8 = M[0][1];
+const M10: u8 = M[1][0];
+const ROW: [u8; 2] = M[1];
+
+fn main() {
+    assert_eq!(M01, 2);
+    assert_eq!(M10, 3);
+    assert_eq!(ROW, [3, 4]);
+    let _: [(); M[1][1] as usize] = [(); 4];
+}
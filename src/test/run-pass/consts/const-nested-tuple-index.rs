@@ -0,0 +1,20 @@
+// run-pass
+
+// `x.0.1` is lexed as `x` followed by the float `0.1`, so the inner accesses
+// are parenthesized.
+
+const PAIR: ((i32, i32), i32) = ((1, 2), 3);
+
+const X: i32 = (((1, 2), 3).0).1;
+const INNER: (i32, i32) = PAIR.0;
+const DEEP: i32 = ((((1, (2, 3)), 4).0).1).0;
+const DEEPER: u8 = (((((0, (1, (2, 3))), 4).0).1).1).1;
+
+fn main() {
+    assert_eq!(X, 2);
+    assert_eq!(INNER, (1, 2));
+    assert_eq!((PAIR.0).0, 1);
+    assert_eq!(DEEP, 2);
+    assert_eq!(DEEPER, 3);
+    let _: [(); (PAIR.0).1 as usize] = [(); 2];
+}
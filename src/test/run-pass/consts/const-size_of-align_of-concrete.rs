@@ -0,0 +1,26 @@
+// run-pass
+
+use std::mem::{align_of, size_of};
+
+const U32_SIZE: usize = size_of::<u32>();
+const TWO_U64: usize = size_of::<u64>() * 2;
+const U64_ALIGN: usize = align_of::<u64>();
+const U16_ALIGN: usize = align_of::<u16>();
+
+struct Pair(u32, u8);
+
+const PAIR_SIZE: usize = size_of::<Pair>();
+
+// Sizes computed at compile time must be usable as array lengths.
+static BUF: [u8; size_of::<u64>() * 2] = [0; TWO_U64];
+
+fn main() {
+    assert_eq!(U32_SIZE, 4);
+    assert_eq!(TWO_U64, 16);
+    assert_eq!(U16_ALIGN, 2);
+    // The alignment of `u64` is target dependent (4 on some 32-bit targets),
+    // so compare against the runtime value rather than a literal.
+    assert_eq!(U64_ALIGN, align_of::<u64>());
+    assert_eq!(PAIR_SIZE, size_of::<Pair>());
+    assert_eq!(BUF.len(), 16);
+}
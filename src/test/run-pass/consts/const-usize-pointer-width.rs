@@ -0,0 +1,29 @@
+// run-pass
+// Check that `usize` and `isize` arithmetic in constants happens at the
+// target's pointer width.
+
+const N: usize = (1usize << 20) - 1;
+const MAX: usize = !0;
+const HIGH_BIT: usize = 1 << (MAX.count_ones() - 1);
+const SHIFTED: usize = MAX >> 31;
+const NEG: isize = -1 >> 1;
+const ARRAY: [u8; (1usize << 4) - 1] = [0; (1usize << 4) - 1];
+
+fn main() {
+    assert_eq!(N, 0xfffff);
+    assert_eq!(NEG, -1);
+    assert_eq!(ARRAY.len(), 15);
+
+    #[cfg(target_pointer_width = "32")]
+    {
+        assert_eq!(MAX as u64, 0xffff_ffff);
+        assert_eq!(HIGH_BIT as u64, 0x8000_0000);
+        assert_eq!(SHIFTED, 1);
+    }
+    #[cfg(target_pointer_width = "64")]
+    {
+        assert_eq!(MAX as u64, 0xffff_ffff_ffff_ffff);
+        assert_eq!(HIGH_BIT as u64, 0x8000_0000_0000_0000);
+        assert_eq!(SHIFTED, 0x1_ffff_ffff);
+    }
+}
@@ -0,0 +1,37 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=3
+
+// Division by a constant is rewritten into a multiplication and shifts at
+// this optimization level. Compare it against division by a divisor that is
+// only known at runtime.
+
+use std::env;
+
+fn div7_u8(x: u8) -> u8 { x / 7 }
+fn div7_u16(x: u16) -> u16 { x / 7 }
+fn div7_u32(x: u32) -> u32 { x / 7 }
+fn div7_u64(x: u64) -> u64 { x / 7 }
+fn div7_usize(x: usize) -> usize { x / 7 }
+fn div10_u32(x: u32) -> u32 { x / 10 }
+fn div_large_u64(x: u64) -> u64 { x / 0xffff_ffff_ffff_fffd }
+
+fn main() {
+    // Always 7, but opaque to the optimizer.
+    let seven = env::args().count() as u64 + 6;
+
+    for x in 0..=u8::max_value() {
+        assert_eq!(div7_u8(x), x / seven as u8);
+    }
+    for x in 0..=u16::max_value() {
+        assert_eq!(div7_u16(x), x / seven as u16);
+    }
+    let samples = (0..100_000u64).chain((0..100_000).map(|i| u64::max_value() - i))
+        .chain((0..64).map(|shift| 1 << shift));
+    for x in samples {
+        assert_eq!(div7_u32(x as u32), x as u32 / seven as u32);
+        assert_eq!(div10_u32(x as u32), x as u32 / (seven as u32 + 3));
+        assert_eq!(div7_u64(x), x / seven);
+        assert_eq!(div7_usize(x as usize), x as usize / seven as usize);
+        assert_eq!(div_large_u64(x), x / (seven + 0xffff_ffff_ffff_fff6));
+    }
+}
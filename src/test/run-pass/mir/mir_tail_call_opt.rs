@@ -0,0 +1,44 @@
+// run-pass
+// compile-flags: -Z mir-tail-call-opt
+// ignore-emscripten no threads support
+
+use std::thread;
+
+fn sum(n: u64, acc: u64) -> u64 {
+    if n == 0 {
+        acc
+    } else {
+        sum(n - 1, acc + n)
+    }
+}
+
+fn sum_plain(n: u64) -> u64 {
+    if n == 0 {
+        0
+    } else {
+        n + sum_plain(n - 1)
+    }
+}
+
+fn gcd<T: Copy + PartialEq + std::ops::Rem<Output = T>>(a: T, b: T, zero: T) -> T {
+    if b == zero {
+        a
+    } else {
+        gcd(b, a % b, zero)
+    }
+}
+
+fn main() {
+    // Ten million frames would need far more than the 64 KiB of stack the
+    // thread gets.
+    let deep = thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(|| sum(10_000_000, 0))
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(deep, 50_000_005_000_000);
+
+    assert_eq!(sum_plain(100), 5050);
+    assert_eq!(gcd(84u32, 36, 0), 12);
+}
@@ -0,0 +1,38 @@
+// run-pass
+// compile-flags: -Z validate-mir -Z mir-opt-level=3
+
+// Test that the MIR produced by every pass, including the optimizations
+// only enabled at higher opt levels, passes `-Z validate-mir`: switches,
+// drops with unwind edges and inlined calls must all keep their
+// successors in range.
+
+struct Noisy(u32);
+
+impl Drop for Noisy {
+    fn drop(&mut self) {}
+}
+
+#[inline(always)]
+fn classify(x: u32) -> &'static str {
+    match x {
+        0 => "zero",
+        1 | 2 => "small",
+        3..=9 => "medium",
+        _ => "large",
+    }
+}
+
+fn may_panic(n: &Noisy) -> u32 {
+    if n.0 == 100 { panic!() }
+    n.0 * 2
+}
+
+fn main() {
+    let a = Noisy(3);
+    let b = Noisy(may_panic(&a));
+    assert_eq!(classify(a.0), "medium");
+    assert_eq!(classify(b.0), "medium");
+    assert_eq!(classify(0), "zero");
+    assert_eq!(classify(2), "small");
+    assert_eq!(classify(42), "large");
+}
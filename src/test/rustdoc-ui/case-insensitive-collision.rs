@@ -0,0 +1,11 @@
+// compile-pass
+
+// Two items whose pages only differ in case overwrite each other on
+// case-insensitive file systems; make sure rustdoc says so.
+
+#![crate_name = "foo"]
+#![allow(non_camel_case_types)]
+
+pub struct Foo;
+
+pub struct FOO;
@@ -0,0 +1,7 @@
+// compile-pass
+// normalize-stderr-test: "missing-changelog.md`: .*" -> "missing-changelog.md`: $$FILE_NOT_FOUND_MSG"
+
+#![feature(doc_changelog)]
+#![doc(changelog = "missing-changelog.md")]
+
+pub fn foo() {}
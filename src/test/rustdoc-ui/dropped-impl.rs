@@ -0,0 +1,15 @@
+// compile-pass
+
+// An inherent impl on a primitive that has no `#[doc(primitive)]` page
+// cannot be rendered anywhere; make sure rustdoc says so.
+
+#![feature(no_core, lang_items)]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[lang = "char"]
+impl char {
+    pub fn is_fancy(self) -> bool { true }
+}
@@ -64,3 +64,12 @@ pub fn blargh() {}
 /// \_
 #[doc = "```"]
 pub fn crazy_attrs() {}
+
+/// ```
+/// valid
+/// ```
+///
+///     \_
+///
+/// Indented block after a fenced one
+pub fn indented_after_fenced() {}
@@ -0,0 +1,56 @@
+// compile-flags:-Z unstable-options --api-manifest
+
+#![crate_name = "foo"]
+
+// @has foo/api.txt
+// @has - 'fn foo::add(a: u32, b: u32) -> u32'
+// @has - 'struct foo::Point { x: i32, y: i32 }'
+// @has - 'fn foo::Point::new(x: i32, y: i32) -> Point'
+// @has - 'struct foo::Meters(f64)'
+// @has - 'enum foo::Shape { Circle(f64), Empty }'
+// @has - 'fn foo::shapes::area(shape: &Shape) -> f64'
+// @has - 'impl Clone for Meters'
+// @!has - 'private'
+// @!has - 'helper'
+// @matches - '^enum foo::Shape .*\nfn foo::Point::new.*\nfn foo::add.*\nfn foo::shapes::area'
+
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn helper() {}
+
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    fn private(&self) {}
+}
+
+pub struct Meters(pub f64);
+
+impl Clone for Meters {
+    fn clone(&self) -> Meters {
+        Meters(self.0)
+    }
+}
+
+pub enum Shape {
+    Circle(f64),
+    Empty,
+}
+
+pub mod shapes {
+    pub fn area(shape: &super::Shape) -> f64 {
+        match *shape {
+            super::Shape::Circle(r) => 3.0 * r * r,
+            super::Shape::Empty => 0.0,
+        }
+    }
+}
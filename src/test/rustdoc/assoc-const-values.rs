@@ -0,0 +1,26 @@
+#![crate_name = "foo"]
+
+// @has foo/trait.Limits.html '//pre[@class="rust trait"]' 'const MAX: u32;'
+// @!has - '//pre[@class="rust trait"]' 'const MAX: u32 = 10;'
+// @has - '//*[@id="associatedconstant.MAX"]' 'const MAX: u32 = 10'
+// @has - '//*[@id="associatedconstant.MIN"]' 'const MIN: u32'
+// @!has - '//*[@id="associatedconstant.MIN"]' 'const MIN: u32 ='
+pub trait Limits {
+    const MAX: u32 = 10;
+    const MIN: u32;
+}
+
+pub struct Small;
+
+// @has foo/struct.Small.html '//*[@id="associatedconstant.MAX"]' 'const MAX: u32 = 3'
+// @has - '//*[@id="associatedconstant.MIN"]' 'const MIN: u32 = 1'
+impl Limits for Small {
+    const MAX: u32 = 3;
+    const MIN: u32 = 1;
+}
+
+// @has foo/struct.Small.html '//*[@id="associatedconstant.NAME"]' \
+//      "const NAME: &'static str = \"<small>\""
+impl Small {
+    pub const NAME: &'static str = "<small>";
+}
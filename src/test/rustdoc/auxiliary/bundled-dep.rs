@@ -0,0 +1,5 @@
+pub struct Dep;
+
+pub fn make() -> Dep {
+    Dep
+}
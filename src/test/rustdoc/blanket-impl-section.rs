@@ -0,0 +1,17 @@
+#![crate_name = "foo"]
+
+pub trait Greet {}
+pub trait Loud {}
+
+impl<T: Greet> Loud for T {}
+
+// @has foo/struct.S.html
+// @has - '//h2[@id="blanket-implementations"]' 'Blanket Implementations'
+// @has - '//div[@id="blanket-implementations-list"]//h3[@id="impl-Loud"]//code' \
+//      'impl<T> Loud for T where T: Greet'
+// @!has - '//div[@id="implementations-list"]//h3[@id="impl-Loud"]' ''
+// @has - '//div[@id="implementations-list"]//h3[@id="impl-Greet"]//code' 'impl Greet for S'
+// @!has - '//div[@id="blanket-implementations-list"]//h3[@id="impl-Greet"]' ''
+pub struct S;
+
+impl Greet for S {}
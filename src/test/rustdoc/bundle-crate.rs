@@ -0,0 +1,23 @@
+// ignore-tidy-linelength
+// aux-build:bundled-dep.rs
+// ignore-cross-compile
+// compile-flags:-Z unstable-options --bundle-crate bundled_dep --extern-html-root-url bundled_dep=https://example.com/
+
+#![crate_name = "foo"]
+
+extern crate bundled_dep;
+
+// @has bundled_dep/index.html
+// @has bundled_dep/struct.Dep.html
+// @has bundled_dep/fn.make.html '//a/@href' '../bundled_dep/struct.Dep.html'
+// @!has bundled_dep/fn.make.html '//a/@href' '../bundled_dep/bundled_dep/struct.Dep.html'
+
+// @has search-index.js 'searchIndex["bundled_dep"]'
+// @has - '"Dep","bundled_dep"'
+// @has - 'searchIndex["foo"]'
+// @has - '"take","foo"'
+// @!has - 'bundled_dep::bundled_dep'
+
+// @has foo/fn.take.html '//a/@href' '../bundled_dep/struct.Dep.html'
+// @!has foo/fn.take.html '//a/@href' 'https://example.com/bundled_dep/struct.Dep.html'
+pub fn take(_: bundled_dep::Dep) {}
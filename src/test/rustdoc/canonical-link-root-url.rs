@@ -0,0 +1,16 @@
+#![crate_name = "foo"]
+#![doc(html_root_url = "https://example.com/foo/0.1.0")]
+
+pub mod a {
+    // @has foo/a/struct.Foo.html '//link[@rel="canonical"]/@href' \
+    //      'https://example.com/foo/0.1.0/foo/a/struct.Foo.html'
+    pub struct Foo;
+}
+
+// @has foo/index.html '//link[@rel="canonical"]/@href' \
+//      'https://example.com/foo/0.1.0/foo/index.html'
+
+// @has foo/struct.Foo.html '//link[@rel="canonical"]/@href' \
+//      'https://example.com/foo/0.1.0/foo/a/struct.Foo.html'
+#[doc(inline)]
+pub use a::Foo;
@@ -0,0 +1,23 @@
+#![crate_name = "foo"]
+
+pub mod a {
+    // @has foo/a/struct.Foo.html '//link[@rel="canonical"]/@href' '../../foo/a/struct.Foo.html'
+    pub struct Foo;
+
+    // @has foo/a/trait.Bar.html '//link[@rel="canonical"]/@href' '../../foo/a/trait.Bar.html'
+    pub trait Bar {}
+}
+
+mod hidden {
+    // @has foo/struct.Baz.html '//link[@rel="canonical"]/@href' '../foo/struct.Baz.html'
+    pub struct Baz;
+}
+
+// @has foo/index.html '//link[@rel="canonical"]/@href' '../foo/index.html'
+
+// @has foo/struct.Foo.html '//link[@rel="canonical"]/@href' '../foo/a/struct.Foo.html'
+// @has foo/trait.Bar.html '//link[@rel="canonical"]/@href' '../foo/a/trait.Bar.html'
+#[doc(inline)]
+pub use a::{Foo, Bar};
+
+pub use hidden::Baz;
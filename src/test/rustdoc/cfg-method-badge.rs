@@ -0,0 +1,16 @@
+// ignore-windows
+// The `#[cfg(unix)]` method only exists to be documented on Unix.
+
+#![crate_name = "foo"]
+
+pub struct Foo;
+
+impl Foo {
+    // @has foo/struct.Foo.html
+    // @count - '//h4[@id="method.always"]/span[@class="stab portability"]' 0
+    pub fn always() {}
+
+    // @has - '//h4[@id="method.unix_only"]/span[@class="stab portability"]' 'Unix'
+    #[cfg(unix)]
+    pub fn unix_only() {}
+}
@@ -0,0 +1,12 @@
+#![crate_name = "foo"]
+#![feature(doc_changelog)]
+#![doc(changelog = "auxiliary/changelog.md")]
+
+// @has foo/changelog.html
+// @has - '//h1[@class="fqn"]' 'Changelog'
+// @has - '//div[@class="docblock"]/h1' '1.1.0'
+// @has - '//div[@class="docblock"]//code' 'frobnicate'
+// @has - '//div[@class="docblock"]/ul/li' 'Initial release.'
+// @has foo/index.html '//a[@id="changelog"]/@href' 'changelog.html'
+
+pub fn frobnicate() {}
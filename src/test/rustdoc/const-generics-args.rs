@@ -0,0 +1,17 @@
+#![feature(const_generics)]
+#![crate_name = "foo"]
+
+// @has foo/struct.Foo.html '//*[@id="structfield.items"]' 'items: [T; N]'
+pub struct Foo<T, const N: usize> {
+    pub items: [T; N],
+}
+
+// @has foo/struct.Bar.html '//*[@id="structfield.foo"]' 'foo: Foo<u8, 4>'
+pub struct Bar {
+    pub foo: Foo<u8, 4>,
+}
+
+// @has foo/struct.Baz.html '//*[@id="structfield.foo"]' 'foo: Foo<u8, N>'
+pub struct Baz<const N: usize> {
+    pub foo: Foo<u8, N>,
+}
@@ -0,0 +1,48 @@
+// compile-flags:-Z unstable-options --doc-coverage-json
+
+#![crate_name = "foo"]
+
+//! The crate.
+
+// @has foo/doc-coverage.json '"total":8'
+// @has - '"documented":4'
+// @has - '"percentage":50.0'
+// @has - '{"documented":true,"kind":"mod","path":"foo","words":2}'
+// @has - '{"documented":true,"kind":"fn","path":"foo::documented","words":5}'
+// @has - '{"documented":false,"kind":"fn","path":"foo::undocumented","words":0}'
+// @has - '{"documented":false,"kind":"struct","path":"foo::Undocumented","words":0}'
+// @has - '{"documented":false,"kind":"struct","path":"foo::Point","words":0}'
+// @has - '{"documented":true,"kind":"method","path":"foo::Point::new","words":6}'
+// @has - '{"documented":true,"kind":"associatedconstant","path":"foo::Point::ORIGIN","words":2}'
+// @has - '{"documented":false,"kind":"method","path":"foo::Undocumented::new","words":0}'
+// @!has - '"path":"foo::new"'
+// @!has - '"path":"foo::ORIGIN"'
+// @!has - 'private'
+
+/// Adds one to a number.
+pub fn documented() {}
+
+pub fn undocumented() {}
+
+pub struct Undocumented;
+
+impl Undocumented {
+    pub fn new() -> Undocumented {
+        Undocumented
+    }
+}
+
+pub struct Point;
+
+impl Point {
+    /// The origin.
+    pub const ORIGIN: Point = Point;
+
+    /// Makes a point at the origin.
+    pub fn new() -> Point {
+        Point
+    }
+}
+
+/// Not public.
+fn private() {}
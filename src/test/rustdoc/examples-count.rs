@@ -0,0 +1,27 @@
+#![crate_name = "foo"]
+
+// @has foo/fn.two.html '//div[@class="examples-count"]' '2 examples'
+/// Adds nothing.
+///
+/// ```
+/// foo::two();
+/// ```
+///
+/// ```text
+/// not an example
+/// ```
+///
+/// ```rust
+/// let () = foo::two();
+/// ```
+pub fn two() {}
+
+// @has foo/fn.one.html '//div[@class="examples-count"]' '1 example'
+/// ```
+/// foo::one();
+/// ```
+pub fn one() {}
+
+// @count foo/fn.none.html '//div[@class="examples-count"]' 0
+/// Nothing to see here.
+pub fn none() {}
@@ -0,0 +1,6 @@
+use foo::parse_config;
+
+fn main() {
+    let config = parse_config("verbose = true");
+    assert!(config.verbose);
+}
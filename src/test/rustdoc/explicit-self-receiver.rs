@@ -0,0 +1,24 @@
+#![crate_name = "foo"]
+#![feature(arbitrary_self_types)]
+
+use std::pin::Pin;
+
+pub struct Foo;
+
+// @has foo/struct.Foo.html
+impl Foo {
+    // @has - '//*[@id="method.boxed"]//code' 'fn boxed(self: Box<Self>)'
+    pub fn boxed(self: Box<Self>) {}
+    // @has - '//*[@id="method.pinned"]//code' 'fn pinned(self: Pin<&mut Self>)'
+    pub fn pinned(self: Pin<&mut Self>) {}
+    // @has - '//*[@id="method.by_ref"]//code' 'fn by_ref(&self)'
+    pub fn by_ref(&self) {}
+}
+
+// @has foo/trait.Bar.html
+pub trait Bar {
+    // @has - '//*[@id="tymethod.boxed"]//code' 'fn boxed(self: Box<Self>)'
+    fn boxed(self: Box<Self>);
+    // @has - '//*[@id="tymethod.pinned"]//code' 'fn pinned(self: Pin<&mut Self>)'
+    fn pinned(self: Pin<&mut Self>);
+}
@@ -0,0 +1,21 @@
+#![crate_name = "foo"]
+
+pub mod inner {
+    pub struct Local;
+}
+
+// @has foo/index.html '//h2[@id="reexports"]/following-sibling::table[1]//code' \
+//      'pub use inner::Local;'
+// @!has foo/index.html '//h2[@id="external-reexports"]/following-sibling::table[1]//code' \
+//      'pub use inner::Local;'
+#[doc(no_inline)]
+pub use inner::Local;
+
+// @has foo/index.html '//h2[@id="external-reexports"]/following-sibling::table[1]//code' \
+//      'pub use std::vec::Vec;'
+// @!has foo/index.html '//h2[@id="reexports"]/following-sibling::table[1]//code' \
+//      'pub use std::vec::Vec;'
+// @has foo/index.html '//div[@class="block items"]//a[@href="#external-reexports"]' \
+//      'Re-exports from other crates'
+#[doc(no_inline)]
+pub use std::vec::Vec;
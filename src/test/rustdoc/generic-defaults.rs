@@ -0,0 +1,19 @@
+#![crate_name = "foo"]
+
+// @has foo/struct.S.html '//pre[@class="rust struct"]' \
+//      "pub struct S<T = u8, U = Vec<T>>"
+pub struct S<T = u8, U = Vec<T>>(T, U);
+
+// @has foo/enum.E.html '//pre[@class="rust enum"]' "pub enum E<T = u32>"
+pub enum E<T = u32> {
+    V(T),
+}
+
+// @has foo/type.Alias.html '//pre[@class="rust typedef"]' \
+//      "type Alias<T = i64> = S<T, ()>;"
+pub type Alias<T = i64> = S<T, ()>;
+
+// @has foo/trait.Tr.html '//pre[@class="rust trait"]' "pub trait Tr<Rhs = Self>"
+pub trait Tr<Rhs = Self> {
+    fn method(&self, rhs: Rhs);
+}
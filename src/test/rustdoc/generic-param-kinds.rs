@@ -0,0 +1,10 @@
+#![crate_name = "foo"]
+#![feature(const_generics)]
+
+use std::marker::PhantomData;
+
+// @has foo/struct.Matrix.html '//pre[@class="rust struct"]' \
+//      "pub struct Matrix<'a, T, const R: usize>"
+pub struct Matrix<'a, T, const R: usize> {
+    rows: PhantomData<&'a T>,
+}
@@ -0,0 +1,13 @@
+#![crate_name = "foo"]
+#![feature(staged_api)]
+#![stable(feature = "rust1", since = "1.0.0")]
+
+// @has foo/index.html
+// @!has - '//h3[@class="stability-group"]'
+// @count - '//table' 1
+
+#[stable(feature = "rust1", since = "1.0.0")]
+pub fn alpha() {}
+
+#[unstable(feature = "foo", issue = "0")]
+pub fn beta() {}
@@ -0,0 +1,35 @@
+// compile-flags: -Z unstable-options --group-by-stability
+
+#![crate_name = "foo"]
+#![feature(staged_api)]
+#![stable(feature = "rust1", since = "1.0.0")]
+
+// @has foo/index.html
+// @count - '//h3[@class="stability-group"]' 3
+// @has - '//h3[@class="stability-group"][1]' 'Stable'
+// @has - '//h3[@class="stability-group"][2]' 'Unstable'
+// @has - '//h3[@class="stability-group"][3]' 'Deprecated'
+// @has - '//table[1]//tr[1]//a' 'alpha'
+// @has - '//table[1]//tr[2]//a' 'beta'
+// @count - '//table[1]//tr' 2
+// @has - '//table[2]//tr[1]//a' 'gamma'
+// @has - '//table[2]//tr[2]//a' 'zeta'
+// @count - '//table[2]//tr' 2
+// @has - '//table[3]//tr[1]//a' 'delta'
+// @count - '//table[3]//tr' 1
+
+#[stable(feature = "rust1", since = "1.0.0")]
+pub fn beta() {}
+
+#[unstable(feature = "foo", issue = "0")]
+pub fn zeta() {}
+
+#[stable(feature = "rust1", since = "1.0.0")]
+#[rustc_deprecated(since = "1.0.0", reason = "use `beta`")]
+pub fn delta() {}
+
+#[stable(feature = "rust1", since = "1.0.0")]
+pub fn alpha() {}
+
+#[unstable(feature = "foo", issue = "0")]
+pub fn gamma() {}
@@ -0,0 +1,21 @@
+// compile-flags:-Z unstable-options --hide-blanket-impls
+
+#![crate_name = "foo"]
+
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl<T: Clone> Describe for T {
+    fn describe(&self) -> String {
+        String::new()
+    }
+}
+
+// @has foo/struct.S.html
+// @has - '//*[@id="blanket-implementations-list"]//code' 'Describe for T'
+// @!has - '//*[@id="blanket-implementations-list"]//code' 'Any for T'
+// @!has - '//*[@id="blanket-implementations-list"]//code' 'From<T> for T'
+// @!has - '//*[@class="sidebar-links"]/a' 'Any'
+#[derive(Clone)]
+pub struct S;
@@ -0,0 +1,23 @@
+#![crate_name = "foo"]
+
+// @has foo/fn.bytes.html
+// @has - '//div[@class="impl-trait-return"]' \
+//      'Returns a type implementing Iterator<Item = u8> + Clone'
+// @has - '//div[@class="impl-trait-return"]//a[@class="trait"]' 'Iterator'
+// @has - '//div[@class="impl-trait-return"]//a[@class="trait"]' 'Clone'
+pub fn bytes() -> impl Iterator<Item = u8> + Clone {
+    vec![1, 2, 3].into_iter()
+}
+
+// @has foo/fn.borrowed.html
+// @has - '//div[@class="impl-trait-return"]' 'Returns a type implementing Fn()'
+// @!has - '//div[@class="impl-trait-return"]' "'a"
+pub fn borrowed<'a>(s: &'a str) -> impl Fn() + 'a {
+    move || drop(s)
+}
+
+// @has foo/fn.plain.html
+// @!has - '//div[@class="impl-trait-return"]'
+pub fn plain() -> u8 {
+    0
+}
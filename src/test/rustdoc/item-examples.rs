@@ -0,0 +1,20 @@
+// compile-flags:-Z unstable-options --item-examples
+
+#![crate_name = "foo"]
+
+pub struct Config {
+    pub verbose: bool,
+}
+
+// @has foo/fn.parse_config.html
+// @has - '//h2[@id="item-examples"]' 'Examples'
+// @has - '//pre[@class="rust rust-example-rendered"]' 'let config = parse_config("verbose = true");'
+/// Parses a configuration.
+pub fn parse_config(s: &str) -> Config {
+    Config { verbose: s.contains("verbose") }
+}
+
+// @has foo/fn.without_example.html
+// @!has - '//h2[@id="item-examples"]'
+/// Has no example file.
+pub fn without_example() {}
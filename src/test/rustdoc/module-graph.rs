@@ -0,0 +1,20 @@
+// compile-flags:-Z unstable-options --module-graph
+
+#![crate_name = "foo"]
+
+// @has foo/modules.dot '"foo" [label="foo", URL="index.html"];'
+// @has - '"foo::a" [label="a", URL="a/index.html"];'
+// @has - '"foo::a::b" [label="b", URL="a/b/index.html"];'
+// @has - '"foo::c" [label="c", URL="c/index.html"];'
+// @has - '"foo" -> "foo::a";'
+// @has - '"foo::a" -> "foo::a::b";'
+// @has - '"foo" -> "foo::c";'
+// @!has - 'hidden'
+
+pub mod a {
+    pub mod b {}
+}
+
+pub mod c {}
+
+mod hidden {}
@@ -0,0 +1,27 @@
+#![crate_name = "foo"]
+#![feature(non_exhaustive)]
+
+// @has foo/struct.Config.html
+// @has - '//h2[@id="fields"]' 'Fields (Non-exhaustive)'
+// @has - '//div[@class="docblock non-exhaustive non-exhaustive-struct"]' \
+//      'Non-exhaustive structs could have additional fields added in future.'
+#[non_exhaustive]
+pub struct Config {
+    pub verbose: bool,
+}
+
+// @has foo/enum.Error.html
+// @has - '//h2[@id="variants"]' 'Variants (Non-exhaustive)'
+// @has - '//div[@class="docblock non-exhaustive non-exhaustive-enum"]' \
+//      'Non-exhaustive enums could have additional variants added in future.'
+#[non_exhaustive]
+pub enum Error {
+    NotFound,
+    PermissionDenied,
+}
+
+// @has foo/struct.Plain.html
+// @!has - '//div[@class="docblock non-exhaustive non-exhaustive-struct"]'
+pub struct Plain {
+    pub field: u8,
+}
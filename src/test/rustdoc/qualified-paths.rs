@@ -0,0 +1,19 @@
+// compile-flags: -Z unstable-options --qualified-paths
+
+#![crate_name = "foo"]
+
+pub mod a {
+    pub struct Error;
+}
+
+pub mod b {
+    pub struct Error;
+}
+
+// @has foo/fn.convert.html '//pre[@class="rust fn"]' 'pub fn convert(e: foo::a::Error) -> foo::b::Error'
+// @has - '//pre[@class="rust fn"]/a[@href="../foo/a/struct.Error.html"]' 'Error'
+// @has - '//pre[@class="rust fn"]/a[@href="../foo/b/struct.Error.html"]' 'Error'
+pub fn convert(e: a::Error) -> b::Error {
+    let _ = e;
+    b::Error
+}
@@ -0,0 +1,17 @@
+#![crate_name = "foo"]
+
+mod old {
+    // @has foo/old/struct.Foo.html
+    // @has - '//p/a' '../../foo/new/struct.Foo.html'
+    // @has - 'location.replace("../../foo/new/struct.Foo.html" + location.search + location.hash)'
+    pub struct Foo;
+
+    impl Foo {
+        pub fn bar(&self) {}
+    }
+}
+
+pub mod new {
+    // @has foo/new/struct.Foo.html '//*[@id="method.bar"]' 'fn bar'
+    pub use old::Foo;
+}
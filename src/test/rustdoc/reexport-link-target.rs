@@ -0,0 +1,29 @@
+#![crate_name = "foo"]
+
+// Links to an item that is re-exported with #[doc(inline)] go to its page in
+// the module that defines it, whatever the order the module's items are
+// documented in.
+
+pub mod a {
+    pub struct Foo;
+    pub trait Bar {}
+}
+
+pub struct Root;
+
+pub mod b {
+    // @has foo/b/struct.Root.html '//link[@rel="canonical"]/@href' '../../foo/struct.Root.html'
+    #[doc(inline)]
+    pub use super::Root;
+}
+
+// @has foo/trait.Bar.html '//link[@rel="canonical"]/@href' '../foo/a/trait.Bar.html'
+#[doc(inline)]
+pub use a::{Foo, Bar};
+
+// @has foo/fn.uses.html '//a[@href="../foo/a/struct.Foo.html"]' 'Foo'
+// @has - '//a[@href="../foo/a/trait.Bar.html"]' 'Bar'
+// @has - '//a[@href="../foo/struct.Root.html"]' 'Root'
+// @!has - '//a[@href="../foo/trait.Bar.html"]' 'Bar'
+// @!has - '//a[@href="../foo/b/struct.Root.html"]' 'Root'
+pub fn uses<T: Bar>(_: Foo, _: Root, _: T) {}
@@ -0,0 +1,24 @@
+#![crate_name = "foo"]
+
+pub mod inner {
+    pub mod deep {
+        pub struct Original;
+
+        pub fn helper() {}
+    }
+}
+
+// @has foo/index.html
+// @has - '//code' 'pub use inner::deep::Original as Renamed;'
+// @has - '//td[@class="docblock-short reexport-origin"]' \
+//      'Re-export of foo::inner::deep::Original'
+// @has - '//td[@class="docblock-short reexport-origin"]/a/@href' \
+//      'foo/inner/deep/struct.Original.html'
+pub use inner::deep::Original as Renamed;
+
+// @has - '//td[@class="docblock-short reexport-origin"]/a/@href' \
+//      'foo/inner/deep/fn.helper.html'
+pub use inner::deep::helper;
+
+// @count - '//td[@class="docblock-short reexport-origin"]' 2
+pub use inner::deep::*;
@@ -0,0 +1,28 @@
+#![crate_name = "foo"]
+
+// @has foo/struct.C.html '//pre[@class="rust struct"]//*[@class="docblock attributes"]' \
+//      '#[repr(C)]'
+#[repr(C)]
+pub struct C {
+    pub a: u8,
+    pub b: u32,
+}
+
+// @has foo/enum.Tag.html '//pre[@class="rust enum"]//*[@class="docblock attributes"]' \
+//      '#[repr(u8)]'
+#[repr(u8)]
+pub enum Tag {
+    A = 1,
+    B = 2,
+}
+
+// @has foo/union.U.html '//pre[@class="rust union"]//*[@class="docblock attributes"]' \
+//      '#[repr(C, align(8))]'
+#[repr(C, align(8))]
+pub union U {
+    pub a: u32,
+    pub b: f32,
+}
+
+// @!has foo/struct.Plain.html '//*[@class="docblock attributes"]'
+pub struct Plain;
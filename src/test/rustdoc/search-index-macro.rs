@@ -0,0 +1,23 @@
+#![crate_name = "foo"]
+
+// @has foo/macro.exported_macro.html
+// @has search-index.js '[14,"exported_macro"'
+/// A documented exported macro.
+#[macro_export]
+macro_rules! exported_macro {
+    () => {};
+}
+
+// @!has foo/macro.hidden_macro.html
+// @!has search-index.js 'hidden_macro'
+#[doc(hidden)]
+#[macro_export]
+macro_rules! hidden_macro {
+    () => {};
+}
+
+// @!has foo/macro.local_macro.html
+// @!has search-index.js 'local_macro'
+macro_rules! local_macro {
+    () => {};
+}
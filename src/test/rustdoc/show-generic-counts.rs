@@ -0,0 +1,11 @@
+// compile-flags:-Z unstable-options --show-generic-counts
+
+#![crate_name = "foo"]
+
+// @has foo/index.html '//a[@class="struct"]' 'S'
+// @has - '//span[@class="generic-count"]' '<2>'
+// @has - '//span[@class="generic-count"]/@title' '2 generic parameters'
+// @count - '//span[@class="generic-count"]' 1
+pub struct S<T, U>(pub T, pub U);
+
+pub struct Plain;
@@ -0,0 +1,19 @@
+#![crate_name = "foo"]
+
+pub struct Widget;
+
+impl Widget {
+    pub fn zoom(&self) {}
+    pub fn apply(&self) {}
+}
+
+impl Widget {
+    pub fn measure(&self) {}
+}
+
+// @has foo/struct.Widget.html
+// @has - '//*[@class="sidebar-title"][@href="#methods"]' 'Methods'
+// @count - '//div[@class="sidebar-links"][1]/a' 3
+// @has - '//div[@class="sidebar-links"][1]/a[1]/@href' '#method.apply'
+// @has - '//div[@class="sidebar-links"][1]/a[2]/@href' '#method.measure'
+// @has - '//div[@class="sidebar-links"][1]/a[3]/@href' '#method.zoom'
@@ -0,0 +1,10 @@
+#![crate_name = "foo"]
+
+// ignore-tidy-linelength
+
+pub trait MyTrait {}
+
+// @has foo/trait.Z.html '//pre[@class="rust trait"]' 'pub trait Z: Clone + MyTrait'
+// @has - '//pre[@class="rust trait"]/a[@href="https://doc.rust-lang.org/nightly/core/clone/trait.Clone.html"]' 'Clone'
+// @has - '//pre[@class="rust trait"]/a[@href="../foo/trait.MyTrait.html"]' 'MyTrait'
+pub trait Z: Clone + MyTrait {}
@@ -0,0 +1,13 @@
+#![feature(associated_type_defaults)]
+#![crate_name = "foo"]
+
+// @has foo/trait.Tr.html
+// @has - '//pre[@class="rust trait"]' 'type Item: Clone;'
+// @has - '//pre[@class="rust trait"]' 'type Output = u8;'
+// @matches - '//pre[@class="rust trait"]' '(?s)type Item: Clone;.*type Output = u8;.*fn get'
+pub trait Tr {
+    type Item: Clone;
+    type Output = u8;
+
+    fn get(&self) -> Self::Item;
+}
@@ -0,0 +1,16 @@
+#![crate_name = "foo"]
+
+pub trait Frob {}
+
+// @has foo/struct.S.html
+// @count - '//div[@class="trait-badges"]/a' 3
+// @has - '//div[@class="trait-badges"]/a[@class="trait-badge derived"]' 'Clone'
+// @has - '//div[@class="trait-badges"]/a[@class="trait-badge derived"]' 'Debug'
+// @has - '//div[@class="trait-badges"]/a[@class="trait-badge"][@href="../foo/trait.Frob.html"]' 'Frob'
+#[derive(Clone, Debug)]
+pub struct S;
+
+impl Frob for S {}
+
+// @count foo/struct.Lonely.html '//div[@class="trait-badges"]' 0
+pub struct Lonely;
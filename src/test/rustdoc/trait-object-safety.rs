@@ -0,0 +1,16 @@
+#![crate_name = "foo"]
+
+// @has foo/trait.Safe.html
+// @has - '//div[@class="object-safety"]' 'This trait is object safe.'
+pub trait Safe {
+    fn by_ref(&self);
+}
+
+// @has foo/trait.Unsafe.html
+// @has - '//div[@class="object-safety"]' 'This trait is not object safe because:'
+// @has - '//div[@class="object-safety"]//li' 'method `generic` has generic type parameters'
+// @!has - '//div[@class="object-safety"]//li' 'by_ref'
+pub trait Unsafe {
+    fn by_ref(&self);
+    fn generic<T>(&self, t: T);
+}
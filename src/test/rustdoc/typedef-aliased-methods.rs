@@ -0,0 +1,34 @@
+#![crate_name = "foo"]
+
+pub struct Buffer<T>(Vec<T>);
+
+impl<T> Buffer<T> {
+    pub fn push(&mut self, value: T) {
+        self.0.push(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn private(&self) {}
+}
+
+impl<T> Clone for Buffer<T> where T: Clone {
+    fn clone(&self) -> Self {
+        Buffer(self.0.clone())
+    }
+}
+
+// @has foo/type.Bytes.html
+// @has - '//h2[@id="aliased-methods"]' 'Methods from Buffer<u8>'
+// @count - '//ul[@class="aliased-methods"]/li' 2
+// @has - '//ul[@class="aliased-methods"]/li[1]/a/@href' 'struct.Buffer.html#method.len'
+// @has - '//ul[@class="aliased-methods"]/li[2]/a/@href' 'struct.Buffer.html#method.push'
+// @!has - '//ul[@class="aliased-methods"]//code' 'private'
+// @!has - '//ul[@class="aliased-methods"]//code' 'clone'
+pub type Bytes = Buffer<u8>;
+
+// @has foo/type.Count.html
+// @count - '//h2[@id="aliased-methods"]' 0
+pub type Count = usize;
@@ -0,0 +1,12 @@
+#![crate_name = "foo"]
+
+// @has foo/union.U.html '//pre[@class="rust union"]' 'pub union U'
+// @has - '//pre[@class="rust union"]' 'pub a: u32'
+// @has - '//pre[@class="rust union"]' 'pub b: f32'
+// @has - '//span[@id="structfield.a"]/code' 'a: u32'
+// @has - '//span[@id="structfield.b"]/code' 'b: f32'
+// @has - '//div[@class="union-fields-note"]' 'Reading a field of a union is unsafe'
+pub union U {
+    pub a: u32,
+    pub b: f32,
+}
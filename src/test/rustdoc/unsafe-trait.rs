@@ -0,0 +1,11 @@
+#![crate_name = "foo"]
+
+// @has foo/trait.Foo.html '//pre[@class="rust trait"]' 'pub unsafe trait Foo'
+// @has - '//div[@class="unsafe-trait-note"]' 'unsafe impl'
+pub unsafe trait Foo {}
+
+unsafe impl Foo for u8 {}
+
+// @has foo/trait.Bar.html '//pre[@class="rust trait"]' 'pub trait Bar'
+// @!has - '//div[@class="unsafe-trait-note"]'
+pub trait Bar {}
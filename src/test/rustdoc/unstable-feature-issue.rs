@@ -0,0 +1,14 @@
+#![crate_name = "foo"]
+#![feature(staged_api)]
+#![stable(feature = "rust1", since = "1.0.0")]
+#![doc(issue_tracker_base_url = "https://github.com/rust-lang/rust/issues/")]
+
+// @has foo/fn.experiment.html
+// @has - '//div[@class="stab unstable"]' \
+//      'This is a nightly-only experimental API. (x #123)'
+// @has - '//div[@class="stab unstable"]/code' 'x'
+// @has - '//div[@class="stab unstable"]/a/@href' \
+//      'https://github.com/rust-lang/rust/issues/123'
+#[unstable(feature = "x", issue = "123")]
+pub fn experiment() {}
+
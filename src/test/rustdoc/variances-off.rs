@@ -0,0 +1,5 @@
+#![crate_name = "foo"]
+
+// @has foo/struct.S.html
+// @count - '//div[@class="variances"]' 0
+pub struct S<T>(fn(T));
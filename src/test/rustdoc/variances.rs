@@ -0,0 +1,21 @@
+// compile-flags:-Z unstable-options --show-variances
+
+#![crate_name = "foo"]
+
+// @has foo/struct.S.html '//div[@class="variances"]' 'T is contravariant'
+pub struct S<T>(fn(T));
+
+// @has foo/struct.Mixed.html '//div[@class="variances"]/span[@class="variance"][1]' 'covariant'
+// @has - '//div[@class="variances"]/span[@class="variance"][2]' 'invariant'
+pub struct Mixed<'a, A, B> {
+    pub a: &'a A,
+    pub b: &'a mut B,
+}
+
+// @has foo/enum.E.html '//div[@class="variances"]' 'T is covariant'
+pub enum E<T> {
+    A(T),
+}
+
+// @count foo/struct.Plain.html '//div[@class="variances"]' 0
+pub struct Plain;
@@ -0,0 +1,23 @@
+#![crate_name = "foo"]
+
+use std::fmt::{Debug, Display};
+
+// @has foo/fn.four.html '//pre[@class="rust fn"]' 'where A: Debug, B: Display, C: Clone, D: Copy,'
+// @count - '//pre[@class="rust fn"]/span[@class="where fmt-newline"]/br' 4
+pub fn four<A, B, C, D>(_: A, _: B, _: C, _: D)
+    where A: Debug,
+          B: Display,
+          C: Clone,
+          D: Copy,
+{}
+
+// @has foo/struct.Many.html '//pre[@class="rust struct"]' \
+//      'pub struct Many<A, B>(_, _) where A: Debug, B: Display;'
+// @count - '//pre[@class="rust struct"]/span[@class="where"]/br' 2
+pub struct Many<A, B>(A, B) where A: Debug, B: Display;
+
+// @has foo/struct.One.html '//pre[@class="rust struct"]' \
+//      'pub struct One<A>(_) where A: Debug;'
+// @has - '//pre[@class="rust struct"]/span[@class="where fmt-inline"]' 'where A: Debug'
+// @count - '//pre[@class="rust struct"]//br' 0
+pub struct One<A>(A) where A: Debug;
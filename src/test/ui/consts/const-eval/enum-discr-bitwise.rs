@@ -0,0 +1,35 @@
+// run-pass
+
+#[repr(i32)]
+enum Flag {
+    Read = 0b001,
+    Write = 0b010,
+    Exec = 0b100,
+}
+
+#[repr(u8)]
+enum Mask {
+    Low = 0x0f,
+    High = 0xf0,
+    Mid = 0x3c,
+}
+
+const READ_WRITE: i32 = (Flag::Read as i32) | (Flag::Write as i32);
+const NONE: i32 = (Flag::Read as i32) & (Flag::Exec as i32);
+const ALL: i32 = READ_WRITE | Flag::Exec as i32;
+const LOW_MID: u8 = (Mask::Low as u8) & (Mask::Mid as u8);
+const HIGH_MID: u8 = (Mask::High as u8) & (Mask::Mid as u8);
+const FLIPPED: u8 = (Mask::Low as u8) ^ (Mask::High as u8);
+
+fn main() {
+    assert_eq!(READ_WRITE, 0b011);
+    assert_eq!(NONE, 0);
+    assert_eq!(ALL, 0b111);
+    assert_eq!(LOW_MID, 0x0c);
+    assert_eq!(HIGH_MID, 0x30);
+    assert_eq!(FLIPPED, 0xff);
+
+    // Array lengths are evaluated by the same machinery.
+    let arr = [0u8; (Flag::Write as usize) | (Flag::Exec as usize)];
+    assert_eq!(arr.len(), 6);
+}
@@ -0,0 +1,6 @@
+const M: [[u8; 2]; 2] = [[1, 2], [3, 4]];
+
+static OOB: u8 = M[2][0];
+//~^ ERROR E0080
+
+fn main() {}
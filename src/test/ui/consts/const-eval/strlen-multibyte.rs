@@ -0,0 +1,24 @@
+// run-pass
+
+#![feature(const_str_len, const_str_as_bytes, const_slice_len)]
+
+// `str::len` is the length in bytes, not in chars, both at compile time
+// and at runtime.
+
+const ASCII: &str = "hello";
+const MULTI: &str = "héllo";
+
+const ASCII_LEN: usize = ASCII.len();
+const MULTI_LEN: usize = MULTI.len();
+const MULTI_BYTES_LEN: usize = MULTI.as_bytes().len();
+
+static BUF: [u8; MULTI.len()] = [0; MULTI_BYTES_LEN];
+
+fn main() {
+    assert_eq!(ASCII_LEN, 5);
+    assert_eq!(MULTI_LEN, 6);
+    assert_eq!(MULTI_BYTES_LEN, 6);
+    assert_eq!(MULTI_LEN, MULTI.len());
+    assert_eq!(MULTI.chars().count(), 5);
+    assert_eq!(BUF.len(), 6);
+}
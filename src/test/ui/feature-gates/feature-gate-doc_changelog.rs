@@ -0,0 +1,3 @@
+#![doc(changelog = "CHANGELOG.md")] //~ ERROR: #[doc(changelog = "...")] is experimental
+
+fn main() {}
@@ -0,0 +1,46 @@
+// Test the purity analysis on simple functions.
+
+#![feature(rustc_attrs)]
+
+#[rustc_purity]
+fn add(a: u32, b: u32) -> u32 { //~ ERROR pure
+    a * 2 + b
+}
+
+#[rustc_purity]
+fn add_twice(a: u32, b: u32) -> u32 { //~ ERROR pure
+    add(add(a, b), b)
+}
+
+#[rustc_purity]
+fn factorial(n: u64) -> u64 { //~ ERROR pure
+    if n == 0 { 1 } else { n * factorial(n - 1) }
+}
+
+#[rustc_purity]
+fn write(p: *mut u32) { //~ ERROR impure
+    unsafe { *p = 1; }
+}
+
+#[rustc_purity]
+fn calls_write(x: u32) -> u32 { //~ ERROR impure
+    let mut y = x;
+    write(&mut y);
+    y
+}
+
+#[rustc_purity]
+fn even(n: u32) -> bool { //~ ERROR impure
+    if n == 0 { true } else { odd(n - 1) }
+}
+
+fn odd(n: u32) -> bool {
+    if n == 0 {
+        write(std::ptr::null_mut());
+        false
+    } else {
+        even(n - 1)
+    }
+}
+
+fn main() {}